@@ -1,20 +1,38 @@
 use crate::{
-    config::{ConfigV1, IntoQuestionType, Question, QuestionRendered, QuestionType},
-    constants::STDIN_INDICATOR,
+    cli::{hooks::run_command, AnswersFormat},
+    config::{
+        ConfigV2, IntoQuestionType, Question, QuestionRendered, QuestionType, Validation,
+    },
+    constants::{validation, ANSWER_ENV_PREFIX, ANSWER_VALUE_NAME, STDIN_INDICATOR},
     error::{Error, Result},
-    prompt::ask_question,
+    prompt::{ask_question, confirm},
     renderer::TemplateRenderer,
 };
 use serde_json::{json, Map, Value};
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 /// Collects answers from various sources: pre-hook output, command line arguments, and user prompts
 pub struct AnswerCollector<'a> {
     engine: &'a dyn TemplateRenderer,
     non_interactive: bool,
+    strict: bool,
+    skip_command_prompts: bool,
+    default_answers: Map<String, Value>,
+    answers_format: AnswersFormat,
     template_root: &'a Path,
 }
 
+/// Outcome of collecting the answer to a single question.
+enum Collected {
+    /// The question was answered, defaulted, or deliberately skipped through `ask_if`.
+    Done,
+    /// The question should have been asked but had no answer and no default.
+    Missing { help: String },
+}
+
 #[derive(Debug)]
 pub enum ValidationError {
     JsonSchema(String),
@@ -27,7 +45,40 @@ impl<'a> AnswerCollector<'a> {
         non_interactive: bool,
         template_root: &'a Path,
     ) -> Self {
-        Self { engine, non_interactive, template_root }
+        Self {
+            engine,
+            non_interactive,
+            strict: false,
+            skip_command_prompts: false,
+            default_answers: Map::new(),
+            answers_format: AnswersFormat::default(),
+            template_root,
+        }
+    }
+
+    /// In non-interactive mode, treat every unanswered question without a default as
+    /// missing, not only the ones marked `required`.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets the lowest-priority answers, e.g. from the user defaults file.
+    pub fn with_default_answers(mut self, default_answers: Map<String, Value>) -> Self {
+        self.default_answers = default_answers;
+        self
+    }
+
+    /// Sets how the answers file is read.
+    pub fn with_answers_format(mut self, answers_format: AnswersFormat) -> Self {
+        self.answers_format = answers_format;
+        self
+    }
+
+    /// Runs `secret_command`s without asking for confirmation first.
+    pub fn with_skip_command_prompts(mut self, skip: bool) -> Self {
+        self.skip_command_prompts = skip;
+        self
     }
 
     /// Read content from a reader into a string.
@@ -40,12 +91,37 @@ impl<'a> AnswerCollector<'a> {
     /// Collects answers from all available sources
     pub fn collect_answers(
         &self,
-        config: &ConfigV1,
+        config: &ConfigV2,
         pre_hook_output: Option<String>,
         cli_answers: Option<String>,
         answers_file: Option<PathBuf>,
     ) -> Result<Value> {
-        let mut answers = Map::new();
+        self.collect_answers_and_originals(
+            config,
+            pre_hook_output,
+            cli_answers,
+            answers_file,
+        )
+        .map(|(answers, _)| answers)
+    }
+
+    /// Collects answers like [`Self::collect_answers`], and also returns them as they
+    /// were given, before any question's `transform` ran. Replaying the originals runs
+    /// each transform once rather than on its own output.
+    pub fn collect_answers_and_originals(
+        &self,
+        config: &ConfigV2,
+        pre_hook_output: Option<String>,
+        cli_answers: Option<String>,
+        answers_file: Option<PathBuf>,
+    ) -> Result<(Value, Value)> {
+        // Defaults for questions the template does not ask stay out of its answers
+        let mut answers: Map<String, Value> = self
+            .default_answers
+            .iter()
+            .filter(|(key, _)| config.questions.contains_key(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
 
         // Add answers from pre-hook output
         if let Some(result) = pre_hook_output {
@@ -66,13 +142,28 @@ impl<'a> AnswerCollector<'a> {
             answers.extend(pre_answers);
         }
 
-        // Add answers from JSON file
+        // Add answers from the answers file
         if let Some(file_path) = answers_file {
             log::debug!("Loading answers from file: {}", file_path.display());
-            let file_answers = self.load_answers_from_file(&file_path)?;
+            // `generate` has always ignored answers files that are not objects;
+            // `update` keeps rejecting them.
+            let file_answers =
+                match load_answers_file(&file_path, self.answers_format, config) {
+                    Err(Error::AnswersNotObject) => {
+                        log::warn!(
+                            "Answers file '{}' is not an object, ignoring it",
+                            file_path.display()
+                        );
+                        Map::new()
+                    }
+                    result => result?,
+                };
             answers.extend(file_answers);
         }
 
+        // Add answers from environment variables
+        answers.extend(self.env_answers(config, |name| std::env::var(name).ok())?);
+
         // Add answers from command line arguments
         if let Some(answers_arg) = cli_answers {
             let answers_str = if answers_arg == STDIN_INDICATOR {
@@ -80,40 +171,145 @@ impl<'a> AnswerCollector<'a> {
             } else {
                 answers_arg
             };
-            let cli_answers = self.parse_to_map(&answers_str)?;
+            let cli_answers = parse_to_map(&answers_str)?;
             answers.extend(cli_answers);
         }
 
         // Collect answers for each question through interactive prompts
+        let mut missing = Vec::new();
+        let mut originals = Map::new();
         for (key, question) in &config.questions {
-            self.collect_question_answer(&mut answers, key, question)?;
+            if let Collected::Missing { help } =
+                self.collect_question_answer(&mut answers, &mut originals, key, question)?
+            {
+                if self.strict || question.required {
+                    missing.push(if help.is_empty() {
+                        format!("  - {key}")
+                    } else {
+                        format!("  - {key}: {help}")
+                    });
+                }
+            }
+        }
+        if !missing.is_empty() {
+            return Err(Error::MissingAnswers { questions: missing });
         }
 
-        Ok(Value::Object(answers))
+        self.apply_cross_validations(config, &mut answers, &mut originals)?;
+
+        let mut given = answers.clone();
+        given.extend(originals);
+        Ok((Value::Object(answers), Value::Object(given)))
     }
 
-    /// Collects answer for a single question
+    /// Checks the config-level `validations` against the full answer set.
+    ///
+    /// All failures are reported together. In interactive mode the user can re-answer
+    /// the questions involved in the failed rules until every rule passes.
+    fn apply_cross_validations(
+        &self,
+        config: &ConfigV2,
+        answers: &mut Map<String, Value>,
+        originals: &mut Map<String, Value>,
+    ) -> Result<()> {
+        loop {
+            let failed = self.failed_cross_validations(config, &json!(answers));
+            if failed.is_empty() {
+                return Ok(());
+            }
+
+            let messages: Vec<String> =
+                failed.iter().map(|(message, _)| message.clone()).collect();
+            let mut keys: Vec<&String> =
+                failed.iter().flat_map(|(_, keys)| keys.iter()).collect();
+            keys.sort_by_key(|key| config.questions.get_index_of(*key));
+            keys.dedup();
+
+            let askable: Vec<(&String, &Question)> = keys
+                .into_iter()
+                .filter_map(|key| config.questions.get_key_value(key))
+                .filter(|(key, question)| {
+                    question.render(key, &json!(answers), self.engine).ask_if
+                })
+                .collect();
+
+            if self.non_interactive || askable.is_empty() {
+                return Err(Error::AnswersValidationError { messages });
+            }
+
+            for message in &messages {
+                println!("{message}");
+            }
+            if !confirm(false, "Re-answer the related questions?".to_string())? {
+                return Err(Error::AnswersValidationError { messages });
+            }
+            for (key, question) in askable {
+                self.collect_question_answer(answers, originals, key, question)?;
+            }
+        }
+    }
+
+    /// Returns the rendered error message and the related question keys of every
+    /// config-level validation that fails for `answers`.
+    fn failed_cross_validations(
+        &self,
+        config: &ConfigV2,
+        answers: &Value,
+    ) -> Vec<(String, Vec<String>)> {
+        config
+            .settings
+            .validations
+            .iter()
+            .filter(|rule| {
+                !self.engine.execute_expression(&rule.condition, answers).unwrap_or(true)
+            })
+            .map(|rule| {
+                let message = self
+                    .engine
+                    .render(&rule.error_message, answers, Some("validation_error"))
+                    .unwrap_or_else(|_| "Validation failed".to_string());
+                let keys = if rule.questions.is_empty() {
+                    referenced_questions(&rule.condition, config)
+                } else {
+                    rule.questions.clone()
+                };
+                (message, keys)
+            })
+            .collect()
+    }
+
+    /// Collects answer for a single question. The answer as given, before its
+    /// `transform`, is recorded in `originals`.
     fn collect_question_answer(
         &self,
         answers: &mut Map<String, Value>,
+        originals: &mut Map<String, Value>,
         key: &str,
         question: &crate::config::Question,
-    ) -> Result<()> {
+    ) -> Result<Collected> {
         loop {
             let QuestionRendered { help, default, ask_if, .. } =
                 question.render(key, &json!(answers), self.engine);
 
+            let from_command = ask_if
+                && !answers.contains_key(key)
+                && self.answer_from_command(answers, key, question)?;
+
             // Determine if we should skip interactive prompting based on:
             // 1. User explicitly requested non-interactive mode with --non-interactive flag, OR
-            // 2. The template's ask_if condition evaluated to false for this question
-            let skip_user_prompt = self.non_interactive || !ask_if;
+            // 2. The template's ask_if condition evaluated to false for this question, OR
+            // 3. The answer was obtained through the question's secret_command
+            let skip_user_prompt = self.non_interactive || !ask_if || from_command;
 
             if skip_user_prompt {
                 // Skip to the next question if an answer for this key is already provided
-                if let Some(answer) = answers.get(key) {
+                if let Some(answer) = answers.get(key).cloned() {
+                    originals.insert(key.to_string(), answer.clone());
+                    let answer = self.transform_answer(question, key, answer, answers)?;
+                    answers.insert(key.to_string(), answer.clone());
                     let _answers = Value::Object(answers.clone());
                     if let Err(err) =
-                        self.validate_answer(question, answer, self.engine, &_answers)
+                        self.validate_answer(question, &answer, self.engine, &_answers)
                     {
                         return match err {
                             ValidationError::JsonSchema(msg) => Err(Error::Other(
@@ -129,7 +325,12 @@ impl<'a> AnswerCollector<'a> {
 
                 // Use the template's default value if one was specified
                 if !question.default.is_null() {
-                    answers.insert(key.to_string(), default.clone());
+                    originals.insert(key.to_string(), default.clone());
+                    let default =
+                        self.transform_answer(question, key, default, answers)?;
+                    answers.insert(key.to_string(), default);
+                } else if ask_if {
+                    return Ok(Collected::Missing { help });
                 }
                 break;
             }
@@ -145,6 +346,8 @@ impl<'a> AnswerCollector<'a> {
                 },
             };
 
+            originals.insert(key.to_string(), answer.clone());
+            let answer = self.transform_answer(question, key, answer, answers)?;
             answers.insert(key.to_string(), answer.clone());
             let _answers = Value::Object(answers.clone());
 
@@ -157,35 +360,87 @@ impl<'a> AnswerCollector<'a> {
             }
         }
 
-        Ok(())
+        Ok(Collected::Done)
     }
 
-    /// Load answers from a JSON or YAML file.
-    fn load_answers_from_file(
+    /// Reads answers from environment variables.
+    ///
+    /// A question's own `env` variable takes precedence over the `BAKER_ANSWER_<KEY>`
+    /// convention. Text-like and single-choice answers are taken verbatim; other types are
+    /// parsed as YAML, so `true`, `[a, b]` and JSON documents all work.
+    fn env_answers(
         &self,
-        file_path: &Path,
-    ) -> Result<serde_json::Map<String, serde_json::Value>> {
-        let content = std::fs::read_to_string(file_path).map_err(|e| {
-            Error::Other(anyhow::anyhow!(
-                "Failed to read answers file '{}': {}",
-                file_path.display(),
-                e
-            ))
-        })?;
-        self.parse_to_map(&content)
+        config: &ConfigV2,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Map<String, Value>> {
+        let mut answers = Map::new();
+        for (key, question) in &config.questions {
+            let convention = format!("{ANSWER_ENV_PREFIX}{}", env_var_suffix(key));
+            let Some((name, raw)) = question
+                .env
+                .iter()
+                .chain(std::iter::once(&convention))
+                .find_map(|name| lookup(name).map(|raw| (name, raw)))
+            else {
+                continue;
+            };
+            log::debug!("Reading the answer to '{key}' from ${name}");
+            answers.insert(key.clone(), text_answer(question, raw)?);
+        }
+        Ok(answers)
     }
 
-    fn parse_to_map(
+    /// Runs the question's `secret_command`, if any, and stores its output as the
+    /// answer. Returns whether an answer was obtained.
+    fn answer_from_command(
         &self,
-        buf: &str,
-    ) -> Result<serde_json::Map<String, serde_json::Value>> {
-        if let Ok(value) = serde_json::from_str::<serde_json::Value>(buf) {
-            return Ok(value.as_object().cloned().unwrap_or_default());
+        answers: &mut Map<String, Value>,
+        key: &str,
+        question: &Question,
+    ) -> Result<bool> {
+        let Some(command) = &question.secret_command else {
+            return Ok(false);
+        };
+        let run = confirm(
+            self.skip_command_prompts,
+            format!(
+                "WARNING: This template runs the following command on your system to answer '{key}':\n{command}\nDo you want to run it?"
+            ),
+        )?;
+        if !run {
+            return Ok(false);
         }
-        let value: serde_json::Value = serde_yaml::from_str(buf)?;
-        Ok(value.as_object().cloned().unwrap_or_default())
+        answers.insert(key.to_string(), Value::String(run_command(command)?));
+        Ok(true)
+    }
+
+    /// Applies the question's `transform` filter chain to an answer.
+    ///
+    /// The answer is available to the chain as `value`, next to the answers given so
+    /// far, so `transform: trim | lower` evaluates `value | trim | lower`.
+    fn transform_answer(
+        &self,
+        question: &Question,
+        key: &str,
+        answer: Value,
+        answers: &Map<String, Value>,
+    ) -> Result<Value> {
+        let Some(transform) =
+            question.transform.as_deref().map(str::trim).filter(|t| !t.is_empty())
+        else {
+            return Ok(answer);
+        };
+        let mut context = answers.clone();
+        context.insert(ANSWER_VALUE_NAME.to_string(), answer);
+        self.engine
+            .evaluate_expression(&format!("value | {transform}"), &Value::Object(context))
+            .map_err(|e| Error::AnswerTransformError {
+                question: key.to_string(),
+                e: e.to_string(),
+            })
     }
 
+    /// Checks an answer against its question's type, schema and validation rules.
     fn validate_answer(
         &self,
         question: &Question,
@@ -217,20 +472,45 @@ impl<'a> AnswerCollector<'a> {
                     })?;
                 }
             }
-            _ => {
-                let is_valid = engine
-                    .execute_expression(&question.validation.condition, answers)
-                    .unwrap_or(true);
-
-                if !is_valid {
-                    let error_message = engine
-                        .render(
-                            &question.validation.error_message,
-                            answers,
-                            Some("validation_error"),
-                        )
-                        .unwrap_or_else(|_| "Validation failed".to_string());
-                    return Err(ValidationError::FieldValidation(error_message));
+            question_type => {
+                let is_empty = answer.as_str().is_some_and(str::is_empty);
+                let is_textual = matches!(
+                    question_type,
+                    QuestionType::Text
+                        | QuestionType::Path
+                        | QuestionType::Url
+                        | QuestionType::Date
+                );
+                if is_textual && !question.allow_empty && is_empty {
+                    return Err(ValidationError::FieldValidation(
+                        validation::EMPTY_ANSWER.to_string(),
+                    ));
+                }
+                if let (Some(text), false) = (answer.as_str(), is_empty) {
+                    check_native_type(question, &question_type, text)?;
+                }
+
+                // Rules see the answer as `value` next to the other answers.
+                let mut context = answers.as_object().cloned().unwrap_or_default();
+                context.insert(ANSWER_VALUE_NAME.to_string(), answer.clone());
+                let context = Value::Object(context);
+
+                for rule in &question.validation {
+                    let is_valid = builtin_rules_pass(rule, answer)?
+                        && engine
+                            .execute_expression(&rule.condition, &context)
+                            .unwrap_or(true);
+
+                    if !is_valid {
+                        let error_message = engine
+                            .render(
+                                &rule.error_message,
+                                &context,
+                                Some("validation_error"),
+                            )
+                            .unwrap_or_else(|_| "Validation failed".to_string());
+                        return Err(ValidationError::FieldValidation(error_message));
+                    }
                 }
             }
         }
@@ -265,6 +545,298 @@ impl<'a> AnswerCollector<'a> {
     }
 }
 
+/// Checks that a path, URL or date answer is well formed: URLs must parse, dates must
+/// match the question's `format`, and paths must satisfy the question's `path` options.
+/// Relative paths are resolved against the current directory, like path completion.
+fn check_native_type(
+    question: &Question,
+    question_type: &QuestionType,
+    text: &str,
+) -> Result<(), ValidationError> {
+    match question_type {
+        QuestionType::Path => {
+            let Some(options) = &question.path else {
+                return Ok(());
+            };
+            let path = Path::new(text);
+            if options.must_exist && !path.exists() {
+                return Err(ValidationError::FieldValidation(format!(
+                    "Path '{text}' does not exist"
+                )));
+            }
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if !options.extensions.is_empty()
+                && !options.extensions.iter().any(|allowed| {
+                    allowed.trim_start_matches('.').eq_ignore_ascii_case(extension)
+                })
+            {
+                return Err(ValidationError::FieldValidation(format!(
+                    "Path '{text}' must have one of the extensions: {}",
+                    options.extensions.join(", ")
+                )));
+            }
+        }
+        QuestionType::Url => {
+            url::Url::parse(text).map_err(|e| {
+                ValidationError::FieldValidation(format!(
+                    "'{text}' is not a valid URL: {e}"
+                ))
+            })?;
+        }
+        QuestionType::Date => {
+            let format = question.date_format();
+            chrono::NaiveDate::parse_from_str(text, format).map_err(|e| {
+                ValidationError::FieldValidation(format!(
+                    "'{text}' is not a valid date in the format {format}: {e}"
+                ))
+            })?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Checks the built-in validators of a rule (`regex`, `min_length`, `max_length`,
+/// `one_of`, `not_in`). List answers are checked item by item for `one_of`/`not_in`
+/// and by item count for the length limits.
+fn builtin_rules_pass(
+    rule: &Validation,
+    answer: &Value,
+) -> Result<bool, ValidationError> {
+    let items = match answer {
+        Value::Array(items) => items.as_slice(),
+        single => std::slice::from_ref(single),
+    };
+    let length = match answer {
+        Value::String(s) => Some(s.chars().count()),
+        Value::Array(items) => Some(items.len()),
+        _ => None,
+    };
+
+    if let (Some(pattern), Some(text)) = (&rule.regex, answer.as_str()) {
+        let re = regex::Regex::new(pattern).map_err(|e| {
+            ValidationError::FieldValidation(format!("Invalid regex '{pattern}': {e}"))
+        })?;
+        if !re.is_match(text) {
+            return Ok(false);
+        }
+    }
+    if let Some(length) = length {
+        if rule.min_length.is_some_and(|min| length < min)
+            || rule.max_length.is_some_and(|max| length > max)
+        {
+            return Ok(false);
+        }
+    }
+    if let Some(allowed) = &rule.one_of {
+        if !items.iter().all(|item| allowed.contains(item)) {
+            return Ok(false);
+        }
+    }
+    if let Some(denied) = &rule.not_in {
+        if items.iter().any(|item| denied.contains(item)) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Turns a question key into the suffix of its `BAKER_ANSWER_` variable,
+/// e.g. `project-name` into `PROJECT_NAME`.
+fn env_var_suffix(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+/// Converts an answer given as plain text, e.g. in an environment variable, into
+/// the question's type. Textual answers are kept verbatim, choices are matched
+/// against their values and labels, and anything else is parsed as YAML.
+fn text_answer(question: &Question, raw: String) -> Result<Value> {
+    Ok(match question.into_question_type() {
+        QuestionType::SingleChoice => {
+            let raw = Value::String(raw);
+            question
+                .choices
+                .iter()
+                .find(|choice| choice.matches(&raw))
+                .map_or(raw, |choice| choice.value.clone())
+        }
+        QuestionType::Text
+        | QuestionType::Path
+        | QuestionType::Url
+        | QuestionType::Date => Value::String(raw),
+        _ => serde_yaml::from_str(&raw)?,
+    })
+}
+
+/// Parses a JSON or YAML answers document. Anything but a mapping yields no answers.
+fn parse_to_map(buf: &str) -> Result<Map<String, Value>> {
+    if let Ok(value) = serde_json::from_str::<Value>(buf) {
+        return Ok(value.as_object().cloned().unwrap_or_default());
+    }
+    let value: Value = serde_yaml::from_str(buf)?;
+    Ok(value.as_object().cloned().unwrap_or_default())
+}
+
+/// Load answers from a JSON or YAML file, or from the other formats the
+/// `--answers-file` option accepts.
+///
+/// With [`AnswersFormat::Auto`] the format follows the file name: `.toml` files are
+/// TOML, `.env` and `*.env` files are dotenv files and anything else is JSON or
+/// YAML. Dotenv variables are matched like environment answers (the question key,
+/// its `env` name, then `BAKER_ANSWER_<KEY>`) and unrelated variables are ignored.
+/// A cookiecutter replay file keeps its answers under the `cookiecutter` key; its
+/// private `_`-prefixed entries are dropped.
+///
+/// # Arguments
+/// * `path` - The answers file
+/// * `format` - How to read the file
+/// * `config` - The template configuration, used to type text answers
+///
+/// # Returns
+/// * `Result<Map<String, Value>>` - The answers keyed by question
+pub fn load_answers_file(
+    path: &Path,
+    format: AnswersFormat,
+    config: &ConfigV2,
+) -> Result<Map<String, Value>> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        Error::Other(anyhow::anyhow!(
+            "Failed to read answers file '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    match format {
+        AnswersFormat::CookiecutterReplay => {
+            let replay: Value = serde_json::from_str(&content)?;
+            let Some(Value::Object(context)) = replay.get("cookiecutter") else {
+                return Err(Error::Other(anyhow::anyhow!(
+                    "Cookiecutter replay file '{}' has no 'cookiecutter' object",
+                    path.display()
+                )));
+            };
+            let mut answers = Map::new();
+            for (key, value) in context.iter().filter(|(key, _)| !key.starts_with('_')) {
+                let value = match (value, config.questions.get(key)) {
+                    (Value::String(raw), Some(question)) => {
+                        text_answer(question, raw.clone())?
+                    }
+                    _ => value.clone(),
+                };
+                answers.insert(key.clone(), value);
+            }
+            Ok(answers)
+        }
+        AnswersFormat::Auto if is_dotenv_file(path) => {
+            let vars = parse_dotenv(&content)?;
+            let mut answers = Map::new();
+            for (key, question) in &config.questions {
+                let convention = format!("{ANSWER_ENV_PREFIX}{}", env_var_suffix(key));
+                let raw = std::iter::once(key)
+                    .chain(&question.env)
+                    .chain(std::iter::once(&convention))
+                    .find_map(|name| vars.get(name));
+                if let Some(raw) = raw {
+                    answers.insert(key.clone(), text_answer(question, raw.clone())?);
+                }
+            }
+            Ok(answers)
+        }
+        AnswersFormat::Auto => {
+            let value = if path.extension().is_some_and(|ext| ext == "toml") {
+                toml::from_str(&content)?
+            } else {
+                match serde_json::from_str::<Value>(&content) {
+                    Ok(value) => value,
+                    Err(_) => serde_yaml::from_str(&content)?,
+                }
+            };
+            match value {
+                Value::Object(map) => Ok(map),
+                _ => Err(Error::AnswersNotObject),
+            }
+        }
+    }
+}
+
+fn is_dotenv_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "env")
+        || path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name == ".env" || name.starts_with(".env."))
+}
+
+/// Parses `KEY=value` lines of a dotenv file. Blank lines, `#` comments and an
+/// `export ` prefix are skipped. Values may be single-quoted (taken literally) or
+/// double-quoted (with `\n`, `\t`, `\"` and `\\` escapes); unquoted values end at
+/// ` #`.
+fn parse_dotenv(content: &str) -> Result<HashMap<String, String>> {
+    let mut vars = HashMap::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            return Err(Error::Other(anyhow::anyhow!(
+                "Invalid dotenv line {}: expected KEY=value",
+                number + 1
+            )));
+        };
+        let value = value.trim();
+        let value = if let Some(inner) =
+            value.strip_prefix('\'').and_then(|v| v.strip_suffix('\''))
+        {
+            inner.to_string()
+        } else if let Some(inner) =
+            value.strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+        {
+            unescape_dotenv(inner)
+        } else {
+            value.split(" #").next().unwrap_or_default().trim_end().to_string()
+        };
+        vars.insert(name.trim().to_string(), value);
+    }
+    Ok(vars)
+}
+
+fn unescape_dotenv(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Returns the question keys that appear as identifiers in `condition`.
+fn referenced_questions(condition: &str, config: &ConfigV2) -> Vec<String> {
+    let identifiers: Vec<&str> = condition
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|token| !token.is_empty())
+        .collect();
+    config
+        .questions
+        .keys()
+        .filter(|key| identifiers.contains(&key.as_str()))
+        .cloned()
+        .collect()
+}
+
 #[cfg(test)]
 impl<'a> AnswerCollector<'a> {
     /// Test helper method to access validate_with_schema
@@ -291,7 +863,7 @@ impl<'a> AnswerCollector<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{Type, Validation};
+    use crate::config::{types::get_default_validation, Type};
     use crate::template::get_template_engine;
     use serde_json::json;
 
@@ -340,10 +912,18 @@ mod tests {
             ask_if: String::new(),
             schema,
             schema_file: None,
-            validation: Validation {
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![Validation {
                 condition: condition.to_string(),
                 error_message: error_message.to_string(),
-            },
+                ..get_default_validation()
+            }],
         }
     }
 
@@ -407,10 +987,18 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: None,
-            validation: Validation {
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![Validation {
                 condition: "false".to_string(),
                 error_message: "custom error".to_string(),
-            },
+                ..get_default_validation()
+            }],
         };
 
         let answer = serde_json::json!("anything");
@@ -427,6 +1015,121 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_answer_checks_every_rule_in_order() {
+        let question: Question = serde_yaml::from_str(
+            r#"
+type: str
+validation:
+  - min_length: 3
+    max_length: 10
+    error_message: "'{{ value }}' must be 3-10 characters, {{ author }}"
+  - regex: "^[a-z-]+$"
+    error_message: "'{{ value }}' is not a slug"
+  - not_in: [admin, root]
+    error_message: "'{{ value }}' is reserved"
+  - condition: "value != author"
+    error_message: "must differ from the author"
+"#,
+        )
+        .unwrap();
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, false, &temp_dir);
+        let answers = json!({"author": "jane"});
+        let message = |answer: &str| match collector.test_validate_answer(
+            &question,
+            &json!(answer),
+            &engine,
+            &answers,
+        ) {
+            Ok(()) => None,
+            Err(ValidationError::FieldValidation(msg)) => Some(msg),
+            Err(other) => panic!("unexpected error: {other:?}"),
+        };
+
+        assert_eq!(message("my-app"), None);
+        assert_eq!(message("ab").unwrap(), "'ab' must be 3-10 characters, jane");
+        assert_eq!(message("My_App").unwrap(), "'My_App' is not a slug");
+        assert_eq!(message("admin").unwrap(), "'admin' is reserved");
+        assert_eq!(message("jane").unwrap(), "must differ from the author");
+    }
+
+    #[test]
+    fn test_builtin_rules_apply_to_list_items() {
+        let rule: Validation =
+            serde_yaml::from_str("one_of: [docker, ci]\nmin_length: 1").unwrap();
+
+        assert!(builtin_rules_pass(&rule, &json!(["docker"])).unwrap());
+        assert!(!builtin_rules_pass(&rule, &json!([])).unwrap());
+        assert!(!builtin_rules_pass(&rule, &json!(["docker", "k8s"])).unwrap());
+        assert!(builtin_rules_pass(&rule, &json!("ci")).unwrap());
+    }
+
+    #[test]
+    fn test_validate_answer_checks_native_types() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, false, &temp_dir);
+        let validate = |yaml: &str, answer: Value| {
+            let question: Question = serde_yaml::from_str(yaml).unwrap();
+            collector.test_validate_answer(&question, &answer, &engine, &json!({}))
+        };
+
+        assert!(validate("type: url", json!("https://example.com/x")).is_ok());
+        assert!(validate("type: url", json!("example.com")).is_err());
+        assert!(validate("type: date", json!("2024-02-29")).is_ok());
+        assert!(validate("type: date", json!("2023-02-29")).is_err());
+        assert!(validate("type: date\nformat: \"%d.%m.%Y\"", json!("31.01.2024")).is_ok());
+        assert!(validate("type: date", json!("")).is_ok());
+        assert!(validate("type: date\nallow_empty: false", json!("")).is_err());
+    }
+
+    #[test]
+    fn test_validate_answer_checks_path_options() {
+        let engine = get_template_engine();
+        let tmp = tempfile::tempdir().unwrap();
+        let existing = tmp.path().join("config.toml");
+        std::fs::write(&existing, "").unwrap();
+        let collector = AnswerCollector::new(&engine, false, tmp.path());
+        let question: Question = serde_yaml::from_str(
+            "type: path\npath:\n  must_exist: true\n  extensions: [.toml, yaml]",
+        )
+        .unwrap();
+        let validate = |answer: Value| {
+            collector.test_validate_answer(&question, &answer, &engine, &json!({}))
+        };
+
+        assert!(validate(json!(existing.display().to_string())).is_ok());
+        assert!(matches!(
+            validate(json!(tmp.path().join("missing.toml").display().to_string())),
+            Err(ValidationError::FieldValidation(msg)) if msg.contains("does not exist")
+        ));
+        let wrong_extension = tmp.path().join("config.json");
+        std::fs::write(&wrong_extension, "").unwrap();
+        assert!(matches!(
+            validate(json!(wrong_extension.display().to_string())),
+            Err(ValidationError::FieldValidation(msg)) if msg.contains(".toml, yaml")
+        ));
+    }
+
+    #[test]
+    fn test_validate_answer_resolves_relative_paths_against_the_current_directory() {
+        let engine = get_template_engine();
+        let tmp = tempfile::tempdir().unwrap();
+        let collector = AnswerCollector::new(&engine, false, tmp.path());
+        let question: Question =
+            serde_yaml::from_str("type: path\npath:\n  must_exist: true").unwrap();
+
+        // Tests run from the crate root, which is also where completion would look.
+        assert!(collector
+            .test_validate_answer(&question, &json!("Cargo.toml"), &engine, &json!({}))
+            .is_ok());
+        assert!(collector
+            .test_validate_answer(&question, &json!("missing.toml"), &engine, &json!({}))
+            .is_err());
+    }
+
     #[test]
     fn test_validate_answer_schema_file_valid() {
         let temp_file = tempfile::NamedTempFile::new().unwrap();
@@ -446,10 +1149,18 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: Some(schema_filename),
-            validation: Validation {
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![Validation {
                 condition: "true".to_string(),
                 error_message: "error".to_string(),
-            },
+                ..get_default_validation()
+            }],
         };
 
         let answer = json!({"name": "test"});
@@ -480,10 +1191,18 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: Some(schema_filename),
-            validation: Validation {
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![Validation {
                 condition: "true".to_string(),
                 error_message: "error".to_string(),
-            },
+                ..get_default_validation()
+            }],
         };
 
         let answer = json!({"name": 123}); // Invalid: name should be string
@@ -508,10 +1227,18 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: Some("nonexistent_schema.json".to_string()),
-            validation: Validation {
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![Validation {
                 condition: "true".to_string(),
                 error_message: "error".to_string(),
-            },
+                ..get_default_validation()
+            }],
         };
 
         let answer = json!({"name": "test"});
@@ -546,10 +1273,18 @@ mod tests {
             // Inline schema is different (requires "age" field) but should be ignored
             schema: Some(r#"{"type": "object", "properties": {"age": {"type": "number"}}, "required": ["age"]}"#.to_string()),
             schema_file: Some(schema_filename),
-            validation: Validation {
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![Validation {
                 condition: "true".to_string(),
                 error_message: "error".to_string(),
-            },
+                ..get_default_validation()
+            }],
         };
 
         // This answer has "name" but not "age", so it should pass file_schema but fail inline schema
@@ -568,7 +1303,7 @@ mod tests {
         let engine = get_template_engine();
         let temp_dir = std::env::temp_dir();
         let collector = AnswerCollector::new(&engine, true, &temp_dir);
-        let config: ConfigV1 = serde_json::from_str("{}").unwrap();
+        let config: ConfigV2 = serde_json::from_str("{}").unwrap();
 
         let yaml = "name: alice\nage: 30\n".to_string();
         let result = collector.collect_answers(&config, None, Some(yaml), None).unwrap();
@@ -585,7 +1320,7 @@ mod tests {
         let engine = get_template_engine();
         let temp_dir = std::env::temp_dir();
         let collector = AnswerCollector::new(&engine, true, &temp_dir);
-        let config: ConfigV1 = serde_json::from_str("{}").unwrap();
+        let config: ConfigV2 = serde_json::from_str("{}").unwrap();
 
         let result = collector
             .collect_answers(&config, None, None, Some(temp_file.path().to_path_buf()))
@@ -594,4 +1329,410 @@ mod tests {
         assert_eq!(result["project"], json!("baker"));
         assert_eq!(result["version"], json!(2));
     }
+
+    #[test]
+    fn test_load_answers_from_dotenv_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(".env");
+        std::fs::write(
+            &path,
+            r#"# project answers
+export project_name="My \"App\""
+PORT=8080
+BAKER_ANSWER_USE_DOCKER=true # container build
+DB='postgres # not a comment'
+UNRELATED=ignored
+"#,
+        )
+        .unwrap();
+        let config: ConfigV2 = serde_yaml::from_str(
+            r#"
+questions:
+  project_name:
+    type: str
+  port:
+    type: json
+    env: PORT
+  use_docker:
+    type: bool
+  db:
+    type: str
+    env: DB
+"#,
+        )
+        .unwrap();
+
+        let answers = load_answers_file(&path, AnswersFormat::Auto, &config).unwrap();
+
+        assert_eq!(
+            Value::Object(answers),
+            json!({
+                "project_name": "My \"App\"",
+                "port": 8080,
+                "use_docker": true,
+                "db": "postgres # not a comment",
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_answers_from_cookiecutter_replay() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("service.json");
+        std::fs::write(
+            &path,
+            r#"{
+  "cookiecutter": {
+    "project_name": "demo",
+    "use_docker": "true",
+    "license": "MIT",
+    "_template": "gh:acme/service",
+    "_output_dir": "/tmp"
+  }
+}"#,
+        )
+        .unwrap();
+        let config: ConfigV2 = serde_yaml::from_str(
+            "questions:\n  project_name:\n    type: str\n  use_docker:\n    type: bool\n",
+        )
+        .unwrap();
+
+        let answers =
+            load_answers_file(&path, AnswersFormat::CookiecutterReplay, &config).unwrap();
+        assert_eq!(
+            Value::Object(answers),
+            json!({"project_name": "demo", "use_docker": true, "license": "MIT"})
+        );
+
+        std::fs::write(&path, r#"{"project_name": "demo"}"#).unwrap();
+        let err = load_answers_file(&path, AnswersFormat::CookiecutterReplay, &config)
+            .unwrap_err();
+        assert!(err.to_string().contains("no 'cookiecutter' object"));
+    }
+
+    #[test]
+    fn test_load_answers_file_rejects_non_object_documents() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("answers.yaml");
+        std::fs::write(&path, "- a\n- b\n").unwrap();
+        let config: ConfigV2 = serde_json::from_str("{}").unwrap();
+
+        let err = load_answers_file(&path, AnswersFormat::Auto, &config).unwrap_err();
+        assert!(matches!(err, Error::AnswersNotObject));
+    }
+
+    #[test]
+    fn test_collect_answers_toml_file() {
+        let temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        std::fs::write(
+            temp_file.path(),
+            "project = \"baker\"\nversion = 2\nfeatures = [\"cli\"]\n",
+        )
+        .unwrap();
+
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let config: ConfigV2 = serde_json::from_str("{}").unwrap();
+
+        let result = collector
+            .collect_answers(&config, None, None, Some(temp_file.path().to_path_buf()))
+            .unwrap();
+
+        assert_eq!(result["project"], json!("baker"));
+        assert_eq!(result["version"], json!(2));
+        assert_eq!(result["features"], json!(["cli"]));
+    }
+
+    #[test]
+    fn test_collect_answers_ignores_non_object_answers_file() {
+        let temp_file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        std::fs::write(temp_file.path(), "[1, 2, 3]").unwrap();
+
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let config: ConfigV2 = serde_json::from_str("{}").unwrap();
+
+        let result = collector
+            .collect_answers(
+                &config,
+                None,
+                Some(r#"{"name": "baker"}"#.to_string()),
+                Some(temp_file.path().to_path_buf()),
+            )
+            .unwrap();
+
+        assert_eq!(result, json!({"name": "baker"}));
+    }
+
+    #[test]
+    fn test_collect_answers_default_answers_have_lowest_priority() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let mut defaults = Map::new();
+        defaults.insert("author".to_string(), json!("Jane"));
+        defaults.insert("org".to_string(), json!("jane"));
+        defaults.insert("github_org".to_string(), json!("jane"));
+        let collector =
+            AnswerCollector::new(&engine, true, &temp_dir).with_default_answers(defaults);
+        let config: ConfigV2 = serde_json::from_value(json!({
+            "questions": {
+                "author": {"type": "str"},
+                "org": {"type": "str"}
+            }
+        }))
+        .unwrap();
+
+        let pre_hook = r#"{"org": "acme"}"#.to_string();
+        let result =
+            collector.collect_answers(&config, Some(pre_hook), None, None).unwrap();
+
+        assert_eq!(result, json!({"author": "Jane", "org": "acme"}));
+    }
+
+    #[test]
+    fn test_collect_answers_transforms_before_validation() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let config: ConfigV2 = serde_yaml::from_str(
+            r#"
+questions:
+  project_name:
+    type: str
+    transform: "trim | lower | kebab_case"
+    validation:
+      condition: "project_name == 'my-app'"
+  tags:
+    type: str
+    default: " Web "
+    transform: "trim | lower"
+"#,
+        )
+        .unwrap();
+
+        let answers = r#"{"project_name": "  My App "}"#.to_string();
+        let result =
+            collector.collect_answers(&config, None, Some(answers), None).unwrap();
+
+        assert_eq!(result["project_name"], json!("my-app"));
+        assert_eq!(result["tags"], json!("web"));
+    }
+
+    #[test]
+    fn test_collect_answers_originals_replay_transforms_once() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let config: ConfigV2 = serde_yaml::from_str(
+            r#"
+questions:
+  name:
+    type: str
+    transform: "reverse"
+  suffix:
+    type: str
+    default: "v"
+    transform: "replace('v', 'vv')"
+"#,
+        )
+        .unwrap();
+
+        let collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let (answers, originals) = collector
+            .collect_answers_and_originals(
+                &config,
+                None,
+                Some(r#"{"name": "abc"}"#.to_string()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(answers, json!({"name": "cba", "suffix": "vv"}));
+        assert_eq!(originals, json!({"name": "abc", "suffix": "v"}));
+
+        // Replayed answers come back as the lowest-priority defaults.
+        let Value::Object(replayed) = originals else { unreachable!() };
+        let collector =
+            AnswerCollector::new(&engine, true, &temp_dir).with_default_answers(replayed);
+        let result = collector.collect_answers(&config, None, None, None).unwrap();
+        assert_eq!(result, answers);
+    }
+
+    fn required_config() -> ConfigV2 {
+        serde_yaml::from_str(
+            r#"
+questions:
+  name:
+    type: str
+    help: Project name
+    required: true
+  description:
+    type: str
+  license:
+    type: str
+    help: License
+    required: true
+    allow_empty: false
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_collect_answers_lists_every_missing_required_answer() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let config = required_config();
+
+        let collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let err = collector.collect_answers(&config, None, None, None);
+        assert!(matches!(
+            err,
+            Err(Error::MissingAnswers { ref questions })
+                if questions == &vec![
+                    "  - name: Project name".to_string(),
+                    "  - license: License".to_string(),
+                ]
+        ));
+
+        let collector = AnswerCollector::new(&engine, true, &temp_dir).with_strict(true);
+        let err = collector.collect_answers(&config, None, None, None);
+        assert!(matches!(
+            err,
+            Err(Error::MissingAnswers { ref questions }) if questions.len() == 3
+        ));
+    }
+
+    #[test]
+    fn test_collect_answers_rejects_empty_text_when_not_allowed() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let config = required_config();
+
+        let answers = r#"{"name": "", "license": "MIT"}"#.to_string();
+        assert!(collector.collect_answers(&config, None, Some(answers), None).is_ok());
+
+        let answers = r#"{"name": "baker", "license": ""}"#.to_string();
+        let err = collector.collect_answers(&config, None, Some(answers), None);
+        assert!(err.unwrap_err().to_string().contains(validation::EMPTY_ANSWER));
+    }
+
+    #[test]
+    fn test_env_answers_prefer_question_env_over_convention() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let config: ConfigV2 = serde_yaml::from_str(
+            r#"
+questions:
+  api-token:
+    type: str
+    env: CI_TOKEN
+  use_docker:
+    type: bool
+  features:
+    type: str
+    choices: [a, b]
+    multiselect: true
+  version:
+    type: str
+"#,
+        )
+        .unwrap();
+        let env = |name: &str| match name {
+            "CI_TOKEN" => Some("from-ci".to_string()),
+            "BAKER_ANSWER_API_TOKEN" => Some("ignored".to_string()),
+            "BAKER_ANSWER_USE_DOCKER" => Some("true".to_string()),
+            "BAKER_ANSWER_FEATURES" => Some("[a, b]".to_string()),
+            "BAKER_ANSWER_VERSION" => Some("1.0".to_string()),
+            _ => None,
+        };
+
+        let answers = collector.env_answers(&config, env).unwrap();
+
+        assert_eq!(answers["api-token"], json!("from-ci"));
+        assert_eq!(answers["use_docker"], json!(true));
+        assert_eq!(answers["features"], json!(["a", "b"]));
+        assert_eq!(answers["version"], json!("1.0"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_answers_runs_secret_command_for_missing_answers() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, true, &temp_dir)
+            .with_skip_command_prompts(true);
+        let config: ConfigV2 = serde_yaml::from_str(
+            r#"
+questions:
+  api_key:
+    type: str
+    secret_command: "echo from-store"
+  db_password:
+    type: str
+    secret_command: "exit 1"
+"#,
+        )
+        .unwrap();
+
+        let answers = r#"{"db_password": "given"}"#.to_string();
+        let result =
+            collector.collect_answers(&config, None, Some(answers), None).unwrap();
+
+        assert_eq!(result["api_key"], json!("from-store"));
+        assert_eq!(result["db_password"], json!("given"));
+        assert_eq!(crate::generated::strip_secret_answers(&result, &config), json!({}));
+    }
+
+    fn cross_validation_config() -> ConfigV2 {
+        serde_yaml::from_str(
+            r#"
+questions:
+  grpc:
+    type: bool
+  async_runtime:
+    type: str
+validations:
+  - condition: "not grpc or async_runtime"
+    error_message: "grpc requires async_runtime"
+  - condition: "async_runtime in ['tokio', 'async-std']"
+    error_message: "'{{ async_runtime }}' is not a runtime"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_collect_answers_reports_all_failed_cross_validations() {
+        let engine = get_template_engine();
+        let temp_dir = std::env::temp_dir();
+        let collector = AnswerCollector::new(&engine, true, &temp_dir);
+        let config = cross_validation_config();
+
+        let answers = r#"{"grpc": true, "async_runtime": "tokio"}"#.to_string();
+        assert!(collector.collect_answers(&config, None, Some(answers), None).is_ok());
+
+        let answers = r#"{"grpc": true, "async_runtime": ""}"#.to_string();
+        let err = collector.collect_answers(&config, None, Some(answers), None);
+        assert!(matches!(
+            err,
+            Err(Error::AnswersValidationError { ref messages })
+                if messages == &vec![
+                    "grpc requires async_runtime".to_string(),
+                    "'' is not a runtime".to_string(),
+                ]
+        ));
+    }
+
+    #[test]
+    fn test_referenced_questions_match_identifiers_only() {
+        let config = cross_validation_config();
+        assert_eq!(
+            referenced_questions("not grpc or async_runtime", &config),
+            vec!["grpc".to_string(), "async_runtime".to_string()]
+        );
+        assert!(referenced_questions("grpcx == 1", &config).is_empty());
+    }
 }
@@ -3,6 +3,7 @@ use crate::constants::{exit_codes, verbosity};
 use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand, ValueEnum};
 use log::LevelFilter;
 use std::fmt::Display;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 const HELP_TEMPLATE: &str = r#"{about-section}
@@ -35,6 +36,30 @@ impl Display for SkipConfirm {
     }
 }
 
+/// What to do when a template file cannot be rendered or written.
+#[derive(Debug, Clone, ValueEnum, Copy, PartialEq, Default)]
+#[value(rename_all = "lowercase")]
+pub enum OnError {
+    /// Stop without writing anything and list every file that failed.
+    #[default]
+    Fail,
+    /// Generate the other files, warn about each failure and exit with an error.
+    Warn,
+    /// Generate the other files and leave the failed ones out.
+    Skip,
+}
+
+/// Formats of the file passed with `--answers-file`.
+#[derive(Debug, Clone, ValueEnum, Copy, PartialEq, Default)]
+#[value(rename_all = "kebab-case")]
+pub enum AnswersFormat {
+    /// Detect from the file extension: TOML, .env, or JSON/YAML.
+    #[default]
+    Auto,
+    /// Cookiecutter replay JSON with the answers under a `cookiecutter` key.
+    CookiecutterReplay,
+}
+
 /// Arguments for the `generate` subcommand.
 #[derive(Parser, Debug)]
 pub struct GenerateArgs {
@@ -54,10 +79,14 @@ pub struct GenerateArgs {
     #[arg(short, long)]
     pub answers: Option<String>,
 
-    /// Path to a JSON file containing predefined answers.
+    /// Path to a JSON, YAML, TOML or .env file containing predefined answers.
     #[arg(long = "answers-file", value_name = "FILE")]
     pub answers_file: Option<PathBuf>,
 
+    /// How to read the answers file.
+    #[arg(long = "answers-format", value_enum, default_value = "auto")]
+    pub answers_format: AnswersFormat,
+
     /// Confirmation prompts to skip (comma-separated).
     #[arg(long = "skip-confirms", value_delimiter = ',')]
     #[arg(value_enum)]
@@ -67,6 +96,22 @@ pub struct GenerateArgs {
     #[arg(long = "non-interactive")]
     pub non_interactive: bool,
 
+    /// In non-interactive mode, fail on every unanswered question without a default.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Ignore the user defaults file (~/.config/baker/defaults.yaml).
+    #[arg(long = "no-user-config")]
+    pub no_user_config: bool,
+
+    /// Reuse the answers from the last run of this template.
+    #[arg(long, conflicts_with = "no_replay")]
+    pub replay: bool,
+
+    /// Do not record the answers for `--replay`.
+    #[arg(long = "no-replay")]
+    pub no_replay: bool,
+
     /// Preview actions without touching the filesystem.
     #[arg(long = "dry-run")]
     pub dry_run: bool,
@@ -78,6 +123,23 @@ pub struct GenerateArgs {
     /// Override the conflict-marker style used during `baker update`.
     #[arg(long = "conflict-style", value_enum)]
     pub conflict_style: Option<ConflictStyle>,
+
+    /// Number of files rendered and written in parallel (default: number of CPUs).
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// What to do when a template file cannot be rendered or written.
+    #[arg(long = "on-error", value_enum, default_value = "fail")]
+    pub on_error: OnError,
+
+    /// Fail on undefined variables in templates instead of rendering them as empty.
+    #[arg(long = "strict-undefined")]
+    pub strict_undefined: bool,
+
+    /// Seed of the `uuid()` and `random_string()` template functions, to reproduce
+    /// a previous run.
+    #[arg(long = "random-seed", value_name = "SEED")]
+    pub random_seed: Option<u64>,
 }
 
 /// Arguments for the `update` subcommand.
@@ -91,10 +153,14 @@ pub struct UpdateArgs {
     #[arg(short, long)]
     pub answers: Option<String>,
 
-    /// Path to a JSON file with extra answers (merged on top of saved answers).
+    /// Path to a JSON, YAML, TOML or .env file with extra answers (merged on top of saved answers).
     #[arg(long = "answers-file", value_name = "FILE")]
     pub answers_file: Option<PathBuf>,
 
+    /// How to read the answers file.
+    #[arg(long = "answers-format", value_enum, default_value = "auto")]
+    pub answers_format: AnswersFormat,
+
     /// Override the conflict-marker style.
     #[arg(long = "conflict-style", value_enum)]
     pub conflict_style: Option<ConflictStyle>,
@@ -111,6 +177,73 @@ pub struct UpdateArgs {
     /// Disable interactive prompts when answers are provided.
     #[arg(long = "non-interactive")]
     pub non_interactive: bool,
+
+    /// In non-interactive mode, fail on every unanswered question without a default.
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Ignore the user defaults file (~/.config/baker/defaults.yaml).
+    #[arg(long = "no-user-config")]
+    pub no_user_config: bool,
+
+    /// Number of files rendered and written in parallel (default: number of CPUs).
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// What to do when a template file cannot be rendered or written.
+    #[arg(long = "on-error", value_enum, default_value = "fail")]
+    pub on_error: OnError,
+
+    /// Fail on undefined variables in templates instead of rendering them as empty.
+    #[arg(long = "strict-undefined")]
+    pub strict_undefined: bool,
+
+    /// Seed of the `uuid()` and `random_string()` template functions, to reproduce
+    /// a previous run.
+    #[arg(long = "random-seed", value_name = "SEED")]
+    pub random_seed: Option<u64>,
+}
+
+/// Arguments for the `lint` subcommand.
+#[derive(Parser, Debug)]
+pub struct LintArgs {
+    /// Template directory or Git repository to check.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: String,
+}
+
+/// Output formats for `baker questions`.
+#[derive(Debug, Clone, ValueEnum, Copy, PartialEq)]
+#[value(rename_all = "kebab-case")]
+pub enum QuestionsFormat {
+    /// JSON Schema describing a valid answers document.
+    JsonSchema,
+    /// Commented YAML answers file to fill in.
+    YamlSkeleton,
+}
+
+/// Arguments for the `questions` subcommand.
+#[derive(Parser, Debug)]
+pub struct QuestionsArgs {
+    /// Template directory or Git repository to export the questions of.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: String,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value = "json-schema")]
+    pub format: QuestionsFormat,
+}
+
+/// Arguments for the `migrate-config` subcommand.
+#[derive(Parser, Debug)]
+pub struct MigrateConfigArgs {
+    /// Local template directory whose configuration is rewritten.
+    #[arg(value_name = "TEMPLATE")]
+    pub template: PathBuf,
+
+    /// Print the migrated configuration instead of writing it.
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 /// Baker subcommands.
@@ -120,6 +253,14 @@ pub enum Commands {
     Generate(GenerateArgs),
     /// Update an existing generated project when the template changes.
     Update(UpdateArgs),
+    /// Check a template's configuration, templates, expressions and hooks.
+    Lint(LintArgs),
+    /// Print the JSON Schema for `baker.yaml`.
+    Schema,
+    /// Export a template's questions as a JSON Schema or a YAML answers skeleton.
+    Questions(QuestionsArgs),
+    /// Rewrite a `schemaVersion: v1` configuration file into `schemaVersion: v2`.
+    MigrateConfig(MigrateConfigArgs),
 }
 
 /// Top-level CLI arguments for Baker.
@@ -230,6 +371,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_lint_and_schema_subcommands() {
+        use clap::Parser;
+        let args = Args::parse_from(["baker", "lint", "template_dir"]);
+        match args.command {
+            Commands::Lint(l) => assert_eq!(l.template, "template_dir"),
+            _ => panic!("expected Lint"),
+        }
+        let args = Args::parse_from(["baker", "schema"]);
+        assert!(matches!(args.command, Commands::Schema));
+    }
+
+    #[test]
+    fn parses_questions_subcommand_formats() {
+        use clap::Parser;
+        let args = Args::parse_from(["baker", "questions", "template_dir"]);
+        match args.command {
+            Commands::Questions(q) => {
+                assert_eq!(q.template, "template_dir");
+                assert_eq!(q.format, QuestionsFormat::JsonSchema);
+            }
+            _ => panic!("expected Questions"),
+        }
+        let args = Args::parse_from([
+            "baker",
+            "questions",
+            "template_dir",
+            "--format",
+            "yaml-skeleton",
+        ]);
+        assert!(matches!(
+            args.command,
+            Commands::Questions(QuestionsArgs {
+                format: QuestionsFormat::YamlSkeleton,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_migrate_config_subcommand() {
+        use clap::Parser;
+        let args =
+            Args::parse_from(["baker", "migrate-config", "template_dir", "--dry-run"]);
+        match args.command {
+            Commands::MigrateConfig(m) => {
+                assert_eq!(m.template, PathBuf::from("template_dir"));
+                assert!(m.dry_run);
+            }
+            _ => panic!("expected MigrateConfig"),
+        }
+    }
+
     #[test]
     fn display_skip_confirm_variants() {
         assert_eq!(SkipConfirm::All.to_string(), "all");
@@ -277,12 +471,41 @@ mod tests {
             "output_dir",
             "--answers-file",
             "/path/to/answers.json",
+            "--answers-format",
+            "cookiecutter-replay",
         ]);
         match args.command {
             Commands::Generate(g) => {
                 assert_eq!(g.answers_file, Some(PathBuf::from("/path/to/answers.json")));
+                assert_eq!(g.answers_format, AnswersFormat::CookiecutterReplay);
             }
             _ => panic!("expected Generate"),
         }
     }
+
+    #[test]
+    fn parses_jobs_argument() {
+        use clap::Parser;
+        let args = Args::parse_from([
+            "baker",
+            "generate",
+            "template_dir",
+            "output_dir",
+            "-j",
+            "4",
+        ]);
+        match args.command {
+            Commands::Generate(g) => assert_eq!(g.jobs, NonZeroUsize::new(4)),
+            _ => panic!("expected Generate"),
+        }
+        assert!(Args::try_parse_from(["baker", "update", "--jobs", "0"]).is_err());
+    }
+
+    #[test]
+    fn replay_conflicts_with_no_replay() {
+        use clap::Parser;
+        let args = ["baker", "generate", "template_dir", "output_dir", "--replay"];
+        assert!(Args::try_parse_from(args).is_ok());
+        assert!(Args::try_parse_from(args.into_iter().chain(["--no-replay"])).is_err());
+    }
 }
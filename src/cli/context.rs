@@ -1,11 +1,17 @@
-use crate::{cli::SkipConfirm, config::ConfigV1, conflict::ConflictStyle};
+use crate::{
+    cli::{OnError, SkipConfirm},
+    config::ConfigV2,
+    conflict::ConflictStyle,
+    renderer::filters::random_seed,
+};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
 /// Shared state describing a single generation run.
 pub struct GenerationContext {
     template_root: PathBuf,
     output_root: PathBuf,
-    config: ConfigV1,
+    config: ConfigV2,
     answers: Option<serde_json::Value>,
     skip_confirms: Vec<SkipConfirm>,
     dry_run: bool,
@@ -13,13 +19,21 @@ pub struct GenerationContext {
     conflict_mode: bool,
     /// The conflict marker style to use (only relevant when conflict_mode is true).
     conflict_style: Option<ConflictStyle>,
+    /// Roots of the parent templates named by `extends`, nearest first.
+    parent_roots: Vec<PathBuf>,
+    /// Number of template files rendered and written at the same time.
+    jobs: NonZeroUsize,
+    /// What to do with template files that cannot be generated.
+    on_error: OnError,
+    /// Seed of the random template functions, saved in the generated metadata.
+    random_seed: u64,
 }
 
 impl GenerationContext {
     pub fn new(
         template_root: PathBuf,
         output_root: PathBuf,
-        config: ConfigV1,
+        config: ConfigV2,
         skip_confirms: Vec<SkipConfirm>,
         dry_run: bool,
         conflict_mode: bool,
@@ -34,6 +48,10 @@ impl GenerationContext {
             dry_run,
             conflict_mode,
             conflict_style,
+            parent_roots: Vec::new(),
+            jobs: NonZeroUsize::MIN,
+            on_error: OnError::default(),
+            random_seed: random_seed(),
         }
     }
 
@@ -41,15 +59,56 @@ impl GenerationContext {
         &self.template_root
     }
 
+    /// The directory whose entries are generated: the template root, or the
+    /// project directory of a cookiecutter template.
+    pub fn content_root(&self) -> PathBuf {
+        match &self.config.cookiecutter {
+            Some(cookiecutter) => self.template_root.join(&cookiecutter.project_dir),
+            None => self.template_root.clone(),
+        }
+    }
+
+    pub fn parent_roots(&self) -> &[PathBuf] {
+        &self.parent_roots
+    }
+
+    pub fn set_parent_roots(&mut self, parent_roots: Vec<PathBuf>) {
+        self.parent_roots = parent_roots;
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs.get()
+    }
+
+    pub fn set_jobs(&mut self, jobs: NonZeroUsize) {
+        self.jobs = jobs;
+    }
+
+    pub fn on_error(&self) -> OnError {
+        self.on_error
+    }
+
+    pub fn set_on_error(&mut self, on_error: OnError) {
+        self.on_error = on_error;
+    }
+
+    pub fn random_seed(&self) -> u64 {
+        self.random_seed
+    }
+
+    pub fn set_random_seed(&mut self, random_seed: u64) {
+        self.random_seed = random_seed;
+    }
+
     pub fn output_root(&self) -> &PathBuf {
         &self.output_root
     }
 
-    pub fn config(&self) -> &ConfigV1 {
+    pub fn config(&self) -> &ConfigV2 {
         &self.config
     }
 
-    pub fn config_mut(&mut self) -> &mut ConfigV1 {
+    pub fn config_mut(&mut self) -> &mut ConfigV2 {
         &mut self.config
     }
 
@@ -67,7 +126,9 @@ impl GenerationContext {
 
     /// The effective conflict style: CLI/config override, then default.
     pub fn conflict_style(&self) -> ConflictStyle {
-        self.conflict_style.or(self.config.conflict_marker_style).unwrap_or_default()
+        self.conflict_style
+            .or(self.config.settings.conflict_marker_style)
+            .unwrap_or_default()
     }
 
     pub fn set_answers(&mut self, answers: serde_json::Value) {
@@ -82,3 +143,8 @@ impl GenerationContext {
         self.answers.as_ref()
     }
 }
+
+/// The number of jobs used when `--jobs` is not given: one per available CPU.
+pub fn default_jobs() -> NonZeroUsize {
+    std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
@@ -128,6 +128,46 @@ pub fn run_hook<P: AsRef<Path>>(
     Ok(stdout_output)
 }
 
+/// Runs a shell command and returns its standard output without the trailing newline.
+///
+/// Standard input and error are inherited so that the command can ask for a passphrase.
+/// The output is only kept in memory.
+///
+/// # Arguments
+/// * `command` - Command line passed to `sh -c` (`cmd /C` on Windows)
+///
+/// # Returns
+/// * `Result<String>` - The command's standard output
+pub fn run_command(command: &str) -> Result<String> {
+    log::debug!("Running command '{command}'");
+
+    #[cfg(windows)]
+    let mut shell = {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    };
+    #[cfg(not(windows))]
+    let mut shell = {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    let output =
+        shell.arg(command).stdin(Stdio::inherit()).stderr(Stdio::inherit()).output()?;
+
+    if !output.status.success() {
+        return Err(Error::CommandExecutionError {
+            command: command.to_string(),
+            status: output.status,
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.trim_end_matches(['\n', '\r']).to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,4 +232,14 @@ mod tests {
 
         assert!(output.contains("windows_runner"));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_command_captures_stdout_and_reports_failures() {
+        assert_eq!(run_command("printf 's3cret\\n'").unwrap(), "s3cret");
+        assert!(matches!(
+            run_command("exit 3"),
+            Err(Error::CommandExecutionError { ref command, .. }) if command == "exit 3"
+        ));
+    }
 }
@@ -0,0 +1,294 @@
+//! `baker lint` — static checks for a template: unknown configuration keys, template
+//! syntax, question and validation expressions, and configured hook files.
+
+use crate::{
+    cli::LintArgs,
+    config::{loader::build_globset, schema::find_unknown_keys, Config},
+    constants::{DEFAULT_POST_HOOK, DEFAULT_PRE_HOOK},
+    error::{Error, Result},
+    ignore::parse_bakerignore_file,
+    loader::{extends::resolve_extends, get_template},
+    template::{get_template_engine_for, processor::matches_entry_or_parent},
+};
+use serde_json::json;
+use std::{fmt, path::Path};
+use walkdir::WalkDir;
+
+/// A single problem found by `baker lint`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    /// Where the problem is, e.g. `baker.yaml: questions.name.ask_if` or a file path.
+    pub location: String,
+    /// What is wrong.
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// Main entry point for `baker lint`.
+pub fn run_lint(args: LintArgs) -> Result<()> {
+    let loaded = get_template(&args.template, false)?;
+    let issues = lint_template(&loaded.root)?;
+
+    if issues.is_empty() {
+        println!("No issues found in '{}'.", loaded.root.display());
+        return Ok(());
+    }
+    for issue in &issues {
+        println!("{issue}");
+    }
+    Err(Error::LintFailed { count: issues.len() })
+}
+
+/// Runs every lint check against the template in `template_root`.
+///
+/// Returns `Err` only when the configuration file cannot be found or read; every
+/// other problem is collected into the returned list.
+pub fn lint_template(template_root: &Path) -> Result<Vec<LintIssue>> {
+    let mut issues = Vec::new();
+    let (config_file_name, raw) = Config::load_raw(template_root)?;
+    let issue = |location: String, message: String| LintIssue { location, message };
+
+    for unknown in find_unknown_keys(&raw) {
+        issues.push(issue(config_file_name.to_string(), unknown.to_string()));
+    }
+
+    let (mut config, is_v1) = match Config::load_config_unchecked(template_root) {
+        Ok(config) => {
+            let is_v1 = matches!(config, Config::V1(_));
+            (config.normalize(), is_v1)
+        }
+        Err(e) => {
+            issues.push(issue(config_file_name.to_string(), e.to_string()));
+            return Ok(issues);
+        }
+    };
+    if let Err(e) = config.validate() {
+        issues.push(issue(config_file_name.to_string(), e.to_string()));
+    }
+    if let Err(e) = resolve_extends(template_root, &mut config) {
+        issues.push(issue(format!("{config_file_name}: extends"), e.to_string()));
+    }
+
+    let engine =
+        match get_template_engine_for(config.settings.engine).and_then(|mut engine| {
+            engine.set_delimiters(&config.settings.delimiters)?;
+            Ok(engine)
+        }) {
+            Ok(engine) => engine,
+            Err(e) => {
+                issues.push(issue(format!("{config_file_name}: engine"), e.to_string()));
+                return Ok(issues);
+            }
+        };
+
+    for (name, question) in &config.questions {
+        let location =
+            |field: &str| format!("{config_file_name}: questions.{name}.{field}");
+        for (index, rule) in question.validation.iter().enumerate() {
+            let field = |suffix: &str| match question.validation.len() {
+                1 => location(&format!("validation.{suffix}")),
+                _ => location(&format!("validation[{index}].{suffix}")),
+            };
+            if let Err(e) = engine.check_expression(&rule.condition) {
+                issues.push(issue(field("condition"), e.to_string()));
+            }
+            if let Err(e) = engine.check_template(&rule.error_message, Some(name)) {
+                issues.push(issue(field("error_message"), e.to_string()));
+            }
+            if let Some(Err(e)) = rule.regex.as_deref().map(regex::Regex::new) {
+                issues.push(issue(field("regex"), e.to_string()));
+            }
+        }
+        let checks = [
+            ("ask_if", engine.check_expression(&question.ask_if)),
+            ("help", engine.check_template(&question.help, Some(name))),
+            (
+                "transform",
+                question.transform.as_deref().map_or(Ok(()), |transform| {
+                    engine.check_expression(&format!("value | {transform}"))
+                }),
+            ),
+            (
+                "default",
+                question
+                    .default
+                    .as_str()
+                    .map_or(Ok(()), |default| engine.check_template(default, Some(name))),
+            ),
+        ];
+        for (field, result) in checks {
+            if let Err(e) = result {
+                issues.push(issue(location(field), e.to_string()));
+            }
+        }
+    }
+
+    for (index, rule) in config.settings.validations.iter().enumerate() {
+        let location =
+            |field: &str| format!("{config_file_name}: validations[{index}].{field}");
+        if let Err(e) = engine.check_expression(&rule.condition) {
+            issues.push(issue(location("condition"), e.to_string()));
+        }
+        if let Err(e) =
+            engine.check_template(&rule.error_message, Some("validation_error"))
+        {
+            issues.push(issue(location("error_message"), e.to_string()));
+        }
+        for key in
+            rule.questions.iter().filter(|key| !config.questions.contains_key(*key))
+        {
+            issues
+                .push(issue(location("questions"), format!("unknown question '{key}'")));
+        }
+    }
+
+    let (pre_field, post_field) = if is_v1 {
+        ("pre_hook_filename", "post_hook_filename")
+    } else {
+        ("hooks.pre.filename", "hooks.post.filename")
+    };
+    let hooks = [
+        (
+            pre_field,
+            &config.hooks.pre.filename,
+            DEFAULT_PRE_HOOK,
+            &config.hooks.pre.runner,
+        ),
+        (
+            post_field,
+            &config.hooks.post.filename,
+            DEFAULT_POST_HOOK,
+            &config.hooks.post.runner,
+        ),
+    ];
+    for (field, filename, default, runner) in hooks {
+        let location = format!("{config_file_name}: {field}");
+        match engine.render(filename, &json!({}), Some(filename)) {
+            // Missing default hooks simply mean the template has none.
+            Ok(rendered) if filename == default && runner.is_empty() => {
+                log::debug!("Hook '{rendered}' is optional");
+            }
+            Ok(rendered) => {
+                let hook_path = template_root.join("hooks").join(&rendered);
+                if !hook_path.exists() {
+                    let message = format!("hook file 'hooks/{rendered}' does not exist");
+                    issues.push(issue(location, message));
+                }
+            }
+            Err(e) => issues.push(issue(location, e.to_string())),
+        }
+    }
+
+    let bakerignore = parse_bakerignore_file(template_root)?;
+    // Invalid patterns were already reported by `validate`
+    let render_globs = build_globset(&config.settings.render_globs).unwrap_or_default();
+    let copy_without_render =
+        build_globset(&config.settings.copy_without_render).unwrap_or_default();
+    for entry in
+        WalkDir::new(template_root).min_depth(1).into_iter().filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if bakerignore.is_match(path) {
+            continue;
+        }
+        let Some(rel_path) = path.strip_prefix(template_root).ok().and_then(Path::to_str)
+        else {
+            continue;
+        };
+        let rel_path = rel_path.replace('\\', "/");
+
+        if let Err(e) = engine.check_template(&rel_path, Some(&rel_path)) {
+            issues.push(issue(rel_path.clone(), format!("invalid templated path: {e}")));
+        }
+
+        let relative = Path::new(&rel_path);
+        let has_suffix = rel_path.ends_with(&config.settings.template_suffix);
+        let is_rendered = !matches_entry_or_parent(&copy_without_render, relative)
+            && (has_suffix || matches_entry_or_parent(&render_globs, relative));
+        if entry.file_type().is_file() && is_rendered {
+            match std::fs::read_to_string(path) {
+                Ok(content) => {
+                    if let Err(e) = engine.check_template(&content, Some(&rel_path)) {
+                        issues.push(issue(rel_path, e.to_string()));
+                    }
+                }
+                // Binary files matched by `render_globs` are copied instead
+                Err(e) if !has_suffix && e.kind() == std::io::ErrorKind::InvalidData => {}
+                Err(e) => issues.push(issue(rel_path, e.to_string())),
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn clean_template_has_no_issues() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("baker.yaml"),
+            "schemaVersion: v1\nquestions:\n  name:\n    type: str\n    ask_if: \"true\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("{{name}}.txt.baker.j2"), "Hello {{ name }}").unwrap();
+
+        assert!(lint_template(dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reports_keys_expressions_templates_and_hooks() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("baker.yaml"),
+            r#"schemaVersion: v1
+post_hook_filename: setup
+questions:
+  name:
+    type: str
+    ask-if: "true"
+    validation:
+      condition: "name =="
+"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md.baker.j2"), "{% if name %}unclosed")
+            .unwrap();
+
+        let issues = lint_template(dir.path()).unwrap();
+        let locations: Vec<&str> = issues.iter().map(|i| i.location.as_str()).collect();
+
+        assert!(issues[0].message.contains("did you mean 'ask_if'"));
+        assert!(locations.contains(&"baker.yaml: questions.name.validation.condition"));
+        assert!(locations.contains(&"baker.yaml: post_hook_filename"));
+        assert!(locations.contains(&"README.md.baker.j2"));
+        assert_eq!(issues.len(), 4);
+    }
+
+    #[test]
+    fn checks_files_matched_by_render_globs() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("baker.yaml"),
+            "schemaVersion: v2\nrender_globs: [\"*.toml\"]\ncopy_without_render: [\"vendor\"]\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("app.toml"), "name = \"{{ name\"").unwrap();
+        fs::create_dir(dir.path().join("vendor")).unwrap();
+        fs::write(dir.path().join("vendor/lib.toml.baker.j2"), "{{ unclosed").unwrap();
+
+        let issues = lint_template(dir.path()).unwrap();
+        let locations: Vec<&str> = issues.iter().map(|i| i.location.as_str()).collect();
+        assert_eq!(locations, vec!["app.toml"]);
+    }
+}
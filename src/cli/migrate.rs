@@ -0,0 +1,95 @@
+//! `baker migrate-config` — rewrites a template's `schemaVersion: v1` configuration
+//! file into `schemaVersion: v2`.
+
+use crate::{
+    cli::MigrateConfigArgs,
+    config::{migrate::migrate_to_v2, Config},
+    error::{Error, Result},
+};
+use std::path::Path;
+
+/// Main entry point for `baker migrate-config`.
+pub fn run_migrate_config(args: MigrateConfigArgs) -> Result<()> {
+    let (config_file_name, migrated) = migrate_config(&args.template)?;
+
+    if args.dry_run {
+        print!("{migrated}");
+        return Ok(());
+    }
+    std::fs::write(args.template.join(config_file_name), migrated)?;
+    println!(
+        "Migrated '{}' to schemaVersion v2. Comments in the original file are not kept.",
+        args.template.join(config_file_name).display()
+    );
+    Ok(())
+}
+
+/// Migrates the configuration file of the template in `template_root`, returning the
+/// file name and its new content in the same format (JSON or YAML).
+pub fn migrate_config(template_root: &Path) -> Result<(&'static str, String)> {
+    let (config_file_name, content) = Config::read_config_file(template_root)?;
+    let document = match config_file_name {
+        "baker.toml" => toml::from_str(&content)?,
+        _ => serde_yaml::from_str(&content)?,
+    };
+    let migrated = migrate_to_v2(&document)?;
+
+    let content = match config_file_name {
+        "baker.json" => format!("{}\n", serde_json::to_string_pretty(&migrated)?),
+        "baker.toml" => toml::to_string(&migrated).map_err(|e| Error::Other(e.into()))?,
+        _ => serde_yaml::to_string(&migrated)?,
+    };
+    Ok((config_file_name, content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_json_and_yaml_files_in_their_own_format() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("baker.json"),
+            r#"{"schemaVersion": "v1", "post_hook_runner": ["sh"], "questions": {}}"#,
+        )
+        .unwrap();
+
+        let (name, content) = migrate_config(tmp.path()).unwrap();
+        assert_eq!(name, "baker.json");
+        let migrated: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(
+            migrated,
+            serde_json::json!({
+                "schemaVersion": "v2",
+                "hooks": {"post": {"runner": ["sh"]}},
+                "questions": {}
+            })
+        );
+
+        std::fs::remove_file(tmp.path().join("baker.json")).unwrap();
+        std::fs::write(
+            tmp.path().join("baker.yaml"),
+            "schemaVersion: v1\npre_hook_filename: pre.sh\n",
+        )
+        .unwrap();
+        let (name, content) = migrate_config(tmp.path()).unwrap();
+        assert_eq!(name, "baker.yaml");
+        assert_eq!(content, "schemaVersion: v2\nhooks:\n  pre:\n    filename: pre.sh\n");
+        let config: Config = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(config.normalize().hooks.pre.filename, "pre.sh");
+
+        std::fs::remove_file(tmp.path().join("baker.yaml")).unwrap();
+        std::fs::write(
+            tmp.path().join("baker.toml"),
+            "schemaVersion = \"v1\"\npost_hook_print_stdout = true\n\n[questions.name]\ntype = \"str\"\n",
+        )
+        .unwrap();
+        let (name, content) = migrate_config(tmp.path()).unwrap();
+        assert_eq!(name, "baker.toml");
+        let config: Config = toml::from_str(&content).unwrap();
+        let config = config.normalize();
+        assert!(config.hooks.post.print_stdout);
+        assert!(config.questions.contains_key("name"));
+    }
+}
@@ -2,13 +2,23 @@ pub mod answers;
 pub mod args;
 pub mod context;
 pub mod hooks;
+pub mod lint;
+pub mod migrate;
 pub mod processor;
+pub mod questions;
 pub mod runner;
+pub mod schema;
+pub mod staging;
 pub mod update;
 
 pub use args::{
-    get_args, get_log_level_from_verbose, Args, Commands, GenerateArgs, SkipConfirm,
+    get_args, get_log_level_from_verbose, AnswersFormat, Args, Commands, GenerateArgs,
+    LintArgs, MigrateConfigArgs, OnError, QuestionsArgs, QuestionsFormat, SkipConfirm,
     UpdateArgs,
 };
+pub use lint::run_lint;
+pub use migrate::run_migrate_config;
+pub use questions::run_questions;
 pub use runner::run;
+pub use schema::run_schema;
 pub use update::{run_update, run_update_in_dir};
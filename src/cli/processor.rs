@@ -1,20 +1,32 @@
 use crate::{
-    cli::{context::GenerationContext, SkipConfirm},
+    cli::{context::GenerationContext, staging::StagingDir, OnError, SkipConfirm},
     conflict::apply_conflict_markers,
     error::{Error, Result},
+    ignore::parse_layered_bakerignore_file,
     prompt::confirm,
+    renderer::TemplateRenderer,
     template::{
         operation::{TemplateOperation, WriteOp},
-        processor::TemplateProcessor,
+        processor::{is_skipped_entry, TemplateProcessor},
     },
 };
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use walkdir::WalkDir;
 
+/// An operation of the generation plan, with the name of the template entry it
+/// comes from.
+type PlannedOperation = (String, TemplateOperation);
+
 /// Handles the processing of template files and directories
 pub struct FileProcessor<'a> {
     processor: TemplateProcessor<'a, PathBuf>,
+    /// Processors for the parent templates named by `extends`, nearest first.
+    parents: Vec<TemplateProcessor<'a, PathBuf>>,
     context: &'a GenerationContext,
+    /// Directory the files are written to before they are moved into the output.
+    staging: Option<&'a StagingDir>,
 }
 
 impl<'a> FileProcessor<'a> {
@@ -22,13 +34,155 @@ impl<'a> FileProcessor<'a> {
         processor: TemplateProcessor<'a, PathBuf>,
         context: &'a GenerationContext,
     ) -> Self {
-        Self { processor, context }
+        Self { processor, parents: Vec::new(), context, staging: None }
+    }
+
+    /// Layers the given parent template processors under the main one.
+    pub fn with_parents(mut self, parents: Vec<TemplateProcessor<'a, PathBuf>>) -> Self {
+        self.parents = parents;
+        self
+    }
+
+    /// Writes the generated files into `staging` instead of the output directory.
+    pub fn with_staging(mut self, staging: Option<&'a StagingDir>) -> Self {
+        self.staging = staging;
+        self
+    }
+
+    /// Processes all files in the template directory, then the files of every parent
+    /// template. A parent file is skipped when a descendant already produced the same
+    /// rendered target path.
+    ///
+    /// Generation runs in two phases. The plan phase renders every entry on up to
+    /// [`GenerationContext::jobs`] threads. The apply phase then creates the
+    /// directories, handles the operations that may ask before overwriting one at a
+    /// time, and writes the remaining files in parallel. Operations are logged in the
+    /// order of the entries.
+    ///
+    /// Files that fail in either phase are handled according to
+    /// [`GenerationContext::on_error`]: with [`OnError::Fail`] the whole run fails
+    /// with an error listing all of them, otherwise they are left out and returned.
+    pub fn process_all_files(&self) -> Result<Vec<Error>> {
+        let (operations, mut failures) = self.plan()?;
+        self.check_failures(&failures)?;
+        let jobs = self.context.jobs();
+
+        let (directories, files): (Vec<_>, Vec<_>) =
+            operations.iter().enumerate().partition(|(_, (_, file_operation))| {
+                matches!(file_operation, TemplateOperation::CreateDirectory { .. })
+            });
+        let (prompting, parallel): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|(_, (_, file_operation))| self.may_prompt(file_operation));
+        let mut outcomes: Vec<Option<Result<bool>>> =
+            operations.iter().map(|_| None).collect();
+        for (index, (_, file_operation)) in directories.into_iter().chain(prompting) {
+            outcomes[index] = Some(self.handle_file_operation(file_operation));
+        }
+        let handled = parallel_map(jobs, &parallel, |(_, (_, file_operation))| {
+            self.handle_file_operation(file_operation)
+        });
+        for ((index, _), outcome) in parallel.iter().zip(handled) {
+            outcomes[*index] = Some(outcome);
+        }
+
+        let mut messages = Vec::new();
+        let apply_failures = failures.len();
+        for ((template_name, file_operation), outcome) in operations.iter().zip(outcomes)
+        {
+            match outcome {
+                Some(Ok(user_confirmed_overwrite)) => messages.push(
+                    file_operation
+                        .get_message(user_confirmed_overwrite, self.context.dry_run()),
+                ),
+                Some(Err(e)) => {
+                    let e = Error::FileOperationError {
+                        template: template_name.clone(),
+                        operation: file_operation.error_context(),
+                        e: e.to_string(),
+                    };
+                    self.report_failure(&e);
+                    failures.push(e);
+                }
+                None => {}
+            }
+        }
+        self.check_failures(&failures[apply_failures..])?;
+        for message in messages {
+            log::info!("{message}");
+        }
+        Ok(failures)
+    }
+
+    /// Renders every entry of every layer into the operations that generate it,
+    /// paired with the entry's template name. Entries whose path renders to nothing
+    /// are skipped. Entries that fail to render are returned separately.
+    fn plan(&self) -> Result<(Vec<PlannedOperation>, Vec<Error>)> {
+        let layers: Vec<&TemplateProcessor<'a, PathBuf>> =
+            std::iter::once(&self.processor).chain(&self.parents).collect();
+        let mut entries = Vec::new();
+        for (layer, processor) in layers.iter().enumerate() {
+            entries
+                .extend(self.collect_entries(processor)?.into_iter().map(|e| (layer, e)));
+        }
+
+        let rendered = parallel_map(self.context.jobs(), &entries, |(layer, entry)| {
+            layers[*layer].process(entry.clone())
+        });
+
+        let mut claimed_targets = HashSet::new();
+        let mut operations = Vec::new();
+        let mut failures = Vec::new();
+        for ((_, entry), result) in entries.iter().zip(rendered) {
+            let file_operation = match result {
+                Ok(file_operation) => file_operation,
+                Err(e) if is_skipped_entry(&e) => {
+                    log::debug!("Skipping '{}': {e}", entry.display());
+                    continue;
+                }
+                Err(e) => {
+                    self.report_failure(&e);
+                    failures.push(e);
+                    continue;
+                }
+            };
+            match self.claim_targets(file_operation, &mut claimed_targets) {
+                None | Some(TemplateOperation::Ignore { .. }) => {}
+                Some(file_operation) => {
+                    operations.push((self.get_template_name(entry), file_operation))
+                }
+            }
+        }
+        Ok((operations, failures))
+    }
+
+    /// Logs a file that could not be generated, as far as the error policy asks for.
+    fn report_failure(&self, e: &Error) {
+        match self.context.on_error() {
+            OnError::Fail => {}
+            OnError::Warn => log::warn!("{e}"),
+            OnError::Skip => log::debug!("Skipping file: {e}"),
+        }
+    }
+
+    /// Fails with every error in `failures` when the error policy is
+    /// [`OnError::Fail`] and anything failed.
+    fn check_failures(&self, failures: &[Error]) -> Result<()> {
+        if self.context.on_error() == OnError::Fail && !failures.is_empty() {
+            return Err(generation_failed(failures));
+        }
+        Ok(())
     }
 
-    /// Processes all files in the template directory
-    pub fn process_all_files(&self) -> Result<()> {
-        let walker = WalkDir::new(self.context.template_root())
-            .follow_links(self.context.config().follow_symlinks);
+    /// Lists the entries of a single template layer, skipping symlink loops.
+    fn collect_entries(
+        &self,
+        processor: &TemplateProcessor<'a, PathBuf>,
+    ) -> Result<Vec<PathBuf>> {
+        let template_root = processor.template_root();
+        let walker = WalkDir::new(template_root)
+            .follow_links(self.context.config().settings.follow_symlinks);
+        let mut entries = Vec::new();
         for dir_entry in walker {
             let entry = match dir_entry {
                 Ok(e) => e,
@@ -44,41 +198,66 @@ impl<'a> FileProcessor<'a> {
                     return Err(e.into());
                 }
             };
-            let template_entry = entry.path().to_path_buf();
-            let template_name = self.get_template_name(&template_entry);
-            match self.processor.process(template_entry) {
-                Ok(file_operation) => {
-                    let user_confirmed_overwrite = match &file_operation {
-                        TemplateOperation::Ignore { .. } => continue,
-                        _ => match self.handle_file_operation(&file_operation) {
-                            Ok(confirmed) => confirmed,
-                            Err(e) => {
-                                log::error!(
-                                    "Failed to handle file operation for template '{}' ({}): {e}",
-                                    template_name,
-                                    file_operation.error_context()
-                                );
-                                continue;
-                            }
-                        },
-                    };
-                    let message = file_operation
-                        .get_message(user_confirmed_overwrite, self.context.dry_run());
-                    log::info!("{message}");
-                }
-                Err(e) => match e {
-                    crate::error::Error::ProcessError { .. } => log::warn!("{e}"),
-                    _ => log::error!("{e}"),
-                },
+            entries.push(entry.into_path());
+        }
+        Ok(entries)
+    }
+
+    /// Whether handling `file_operation` may ask the user before overwriting a file.
+    /// Such operations are handled one at a time on the calling thread.
+    fn may_prompt(&self, file_operation: &TemplateOperation) -> bool {
+        match file_operation {
+            TemplateOperation::Write { target_exists, .. }
+            | TemplateOperation::Copy { target_exists, .. } => {
+                !self.should_skip_overwrite_prompt(*target_exists)
             }
+            TemplateOperation::MultipleWrite { writes } => writes
+                .iter()
+                .any(|write| !self.should_skip_overwrite_prompt(write.target_exists)),
+            TemplateOperation::CreateDirectory { .. }
+            | TemplateOperation::Ignore { .. } => false,
+        }
+    }
+
+    /// Records the file targets of `file_operation` and drops the ones an earlier
+    /// (child) layer already produced. Returns `None` when nothing is left to do.
+    fn claim_targets(
+        &self,
+        file_operation: TemplateOperation,
+        claimed_targets: &mut HashSet<PathBuf>,
+    ) -> Option<TemplateOperation> {
+        let mut claim = |target: &Path| {
+            if claimed_targets.insert(target.to_path_buf()) {
+                true
+            } else {
+                log::debug!(
+                    "Skipping '{}': overridden by a child template",
+                    target.display()
+                );
+                false
+            }
+        };
+        match file_operation {
+            TemplateOperation::Write { ref target, .. }
+            | TemplateOperation::Copy { ref target, .. } => {
+                claim(target).then_some(file_operation)
+            }
+            TemplateOperation::MultipleWrite { writes } => {
+                let writes: Vec<WriteOp> =
+                    writes.into_iter().filter(|write| claim(&write.target)).collect();
+                (!writes.is_empty())
+                    .then_some(TemplateOperation::MultipleWrite { writes })
+            }
+            other => Some(other),
         }
-        Ok(())
     }
 
     /// Returns the relative path from template root for use in error messages.
+    /// Paths inside a parent template are made relative to that parent's root.
     fn get_template_name(&self, path: &Path) -> String {
-        path.strip_prefix(self.context.template_root())
-            .ok()
+        std::iter::once(&self.processor)
+            .chain(&self.parents)
+            .find_map(|processor| path.strip_prefix(processor.template_root()).ok())
             .and_then(|p| p.to_str())
             .map(|s| s.replace('\\', "/"))
             .unwrap_or_else(|| path.display().to_string().replace('\\', "/"))
@@ -172,7 +351,7 @@ impl<'a> FileProcessor<'a> {
 
     fn handle_create_dir(&self, target: &Path, target_exists: bool) -> Result<bool> {
         if !target_exists {
-            self.create_dir_all(target)?;
+            self.create_dir_all(self.staged_path(target)?)?;
         }
         Ok(true)
     }
@@ -223,7 +402,7 @@ impl<'a> FileProcessor<'a> {
 
     /// Copy a file from source to destination, creating parent directories if needed.
     fn copy_file<P: AsRef<Path>>(&self, source_path: P, dest_path: P) -> Result<()> {
-        let dest_path = dest_path.as_ref();
+        let dest_path = &self.staged_path(dest_path.as_ref())?;
         let source_path = source_path.as_ref();
 
         if self.context.dry_run() {
@@ -236,7 +415,7 @@ impl<'a> FileProcessor<'a> {
 
         let metadata = std::fs::symlink_metadata(source_path)?;
         if metadata.file_type().is_symlink() {
-            if self.context.config().follow_symlinks {
+            if self.context.config().settings.follow_symlinks {
                 return self.copy_followed_symlink(source_path, dest_path);
             } else {
                 return self.copy_symlink(source_path, dest_path);
@@ -293,7 +472,7 @@ impl<'a> FileProcessor<'a> {
     /// Write content to a file, creating parent directories if needed.
     /// Line endings are converted to the platform default before writing.
     fn write_file<P: AsRef<Path>>(&self, content: &str, dest_path: P) -> Result<()> {
-        let dest_path = dest_path.as_ref();
+        let dest_path = &self.staged_path(dest_path.as_ref())?;
 
         if self.context.dry_run() {
             return Ok(());
@@ -307,6 +486,14 @@ impl<'a> FileProcessor<'a> {
         std::fs::write(dest_path, native).map_err(Error::from)
     }
 
+    /// Returns where `target` is written: its place in the staging directory, if any.
+    fn staged_path(&self, target: &Path) -> Result<PathBuf> {
+        match self.staging {
+            Some(staging) => staging.path_for(target),
+            None => Ok(target.to_path_buf()),
+        }
+    }
+
     /// Create directory and all parent directories if they don't exist.
     fn create_dir_all<P: AsRef<Path>>(&self, dest_path: P) -> Result<()> {
         if self.context.dry_run() {
@@ -324,6 +511,115 @@ impl<'a> FileProcessor<'a> {
     }
 }
 
+/// Processes the template and every parent template it extends, child first.
+///
+/// Each layer is matched against the `.bakerignore` patterns of the whole chain.
+/// Files are generated into a staging directory that is moved into the output
+/// directory only when generation did not fail. Returns the files that could not be
+/// generated but were left out under the error policy, see [`report_failures`].
+pub fn process_template_layers(
+    engine: &dyn TemplateRenderer,
+    context: &GenerationContext,
+) -> Result<Vec<Error>> {
+    let layer_roots: Vec<PathBuf> = std::iter::once(context.content_root())
+        .chain(context.parent_roots().iter().cloned())
+        .collect();
+    let ignores = layer_roots
+        .iter()
+        .map(|root| {
+            let others: Vec<PathBuf> =
+                layer_roots.iter().filter(|other| *other != root).cloned().collect();
+            parse_layered_bakerignore_file(root, &others)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let processor = TemplateProcessor::new(engine, context, &ignores[0])?;
+    let parents = layer_roots[1..]
+        .iter()
+        .zip(&ignores[1..])
+        .map(|(root, bakerignore)| {
+            Ok(TemplateProcessor::new(engine, context, bakerignore)?
+                .with_template_root(root.clone()))
+        })
+        .collect::<Result<_>>()?;
+
+    let staging = (!context.dry_run())
+        .then(|| StagingDir::new(context.output_root()))
+        .transpose()?;
+    let failures = FileProcessor::new(processor, context)
+        .with_parents(parents)
+        .with_staging(staging.as_ref())
+        .process_all_files()?;
+    if let Some(staging) = staging {
+        staging.commit()?;
+    }
+    Ok(failures)
+}
+
+/// Summarizes the files that were left out of a finished generation. Fails with the
+/// list of them, unless the error policy is [`OnError::Skip`].
+pub fn report_failures(on_error: OnError, failures: &[Error]) -> Result<()> {
+    match (on_error, failures.is_empty()) {
+        (_, true) => Ok(()),
+        (OnError::Skip, false) => {
+            log::info!("Skipped {} file(s) that failed to generate", failures.len());
+            Ok(())
+        }
+        (OnError::Fail | OnError::Warn, false) => Err(generation_failed(failures)),
+    }
+}
+
+/// Whether `failures` make the generation fail under the error policy, see
+/// [`report_failures`]. Post hooks are not run for failed generations.
+pub fn is_failed_generation(on_error: OnError, failures: &[Error]) -> bool {
+    !failures.is_empty() && on_error != OnError::Skip
+}
+
+/// Builds the error listing every file in `failures`.
+fn generation_failed(failures: &[Error]) -> Error {
+    Error::GenerationFailed {
+        failures: failures.iter().map(|e| format!("  - {e}")).collect(),
+    }
+}
+
+/// Applies `f` to every item of `items` on up to `jobs` threads and returns the
+/// results in the order of `items`.
+fn parallel_map<T: Sync, R: Send>(
+    jobs: usize,
+    items: &[T],
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let jobs = jobs.min(items.len());
+    if jobs <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else { break };
+                        results.push((index, f(item)));
+                    }
+                    results
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
 /// Returns `true` if `content` contains a baker conflict marker that has not
 /// yet been resolved (i.e. `<<<<<<< current` is still present).
 fn has_unresolved_conflict_markers(content: &str) -> bool {
@@ -371,21 +667,30 @@ mod tests {
         let mut context = GenerationContext::new(
             template_root.path().to_path_buf(),
             output_root.path().to_path_buf(),
-            crate::config::ConfigV1 {
-                template_suffix: ".baker.j2".into(),
-                loop_separator: "".into(),
-                loop_content_separator: "".into(),
-                template_globs: Vec::new(),
-                import_root: None,
+            crate::config::ConfigV2 {
+                settings: crate::config::TemplateSettings {
+                    engine: Default::default(),
+                    delimiters: Default::default(),
+                    template_suffix: ".baker.j2".into(),
+                    loop_separator: "".into(),
+                    loop_content_separator: "".into(),
+                    template_globs: Vec::new(),
+                    import_root: None,
+                    render_globs: Vec::new(),
+                    copy_without_render: Vec::new(),
+                    follow_symlinks,
+                    generated_file_name: None,
+                    conflict_marker_style: None,
+                    extends: None,
+                    validations: Vec::new(),
+                    strict_undefined: false,
+                    prelude: Vec::new(),
+                    globals: Default::default(),
+                    allow_unknown_keys: false,
+                },
                 questions: IndexMap::new(),
-                post_hook_filename: "post".into(),
-                pre_hook_filename: "pre".into(),
-                post_hook_runner: Vec::new(),
-                pre_hook_runner: Vec::new(),
-                post_hook_print_stdout: false,
-                follow_symlinks,
-                generated_file_name: None,
-                conflict_marker_style: None,
+                hooks: Default::default(),
+                cookiecutter: None,
             },
             skip_confirms,
             false,
@@ -394,7 +699,7 @@ mod tests {
         );
         context.set_answers(json!({}));
         let context = Box::leak(Box::new(context));
-        let processor = TemplateProcessor::new(&*engine, context, &*bakerignore);
+        let processor = TemplateProcessor::new(&*engine, context, &*bakerignore).unwrap();
 
         (template_root, output_root, FileProcessor::new(processor, context))
     }
@@ -493,21 +798,30 @@ mod tests {
         let mut context = GenerationContext::new(
             template_root.path().to_path_buf(),
             output_root.path().to_path_buf(),
-            crate::config::ConfigV1 {
-                template_suffix: ".baker.j2".into(),
-                loop_separator: "".into(),
-                loop_content_separator: "".into(),
-                template_globs: Vec::new(),
-                import_root: None,
+            crate::config::ConfigV2 {
+                settings: crate::config::TemplateSettings {
+                    engine: Default::default(),
+                    delimiters: Default::default(),
+                    template_suffix: ".baker.j2".into(),
+                    loop_separator: "".into(),
+                    loop_content_separator: "".into(),
+                    template_globs: Vec::new(),
+                    import_root: None,
+                    render_globs: Vec::new(),
+                    copy_without_render: Vec::new(),
+                    follow_symlinks: false,
+                    generated_file_name: None,
+                    conflict_marker_style: None,
+                    extends: None,
+                    validations: Vec::new(),
+                    strict_undefined: false,
+                    prelude: Vec::new(),
+                    globals: Default::default(),
+                    allow_unknown_keys: false,
+                },
                 questions: IndexMap::new(),
-                post_hook_filename: "post".into(),
-                pre_hook_filename: "pre".into(),
-                post_hook_runner: Vec::new(),
-                pre_hook_runner: Vec::new(),
-                post_hook_print_stdout: false,
-                follow_symlinks: false,
-                generated_file_name: None,
-                conflict_marker_style: None,
+                hooks: Default::default(),
+                cookiecutter: None,
             },
             vec![SkipConfirm::All],
             false,
@@ -516,7 +830,7 @@ mod tests {
         );
         context.set_answers(json!({}));
         let context = Box::leak(Box::new(context));
-        let processor = TemplateProcessor::new(&*engine, context, &*bakerignore);
+        let processor = TemplateProcessor::new(&*engine, context, &*bakerignore).unwrap();
 
         (template_root, output_root, FileProcessor::new(processor, context))
     }
@@ -570,4 +884,124 @@ fn new() {}
         assert!(!dest_link.is_symlink());
         assert_eq!(std::fs::read_to_string(dest_link).unwrap(), "hello-follow");
     }
+
+    #[test]
+    fn parallel_map_keeps_item_order() {
+        let items: Vec<usize> = (0..100).collect();
+        let doubled = parallel_map(8, &items, |item| item * 2);
+        assert_eq!(doubled, items.iter().map(|item| item * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn renders_nested_files_with_several_jobs() {
+        let template_root = TempDir::new().unwrap();
+        let output_root = TempDir::new().unwrap();
+        for dir in 0..5 {
+            let dir_path = template_root.path().join(format!("dir{dir}"));
+            std::fs::create_dir(&dir_path).unwrap();
+            for file in 0..10 {
+                std::fs::write(
+                    dir_path.join(format!("file{file}.txt.baker.j2")),
+                    format!("{{{{ name }}}} {dir}/{file}"),
+                )
+                .unwrap();
+            }
+        }
+        let engine = MiniJinjaRenderer::new();
+        let bakerignore = GlobSetBuilder::new().build().unwrap();
+        let config: crate::config::ConfigV2 = serde_json::from_value(json!({})).unwrap();
+        let mut context = GenerationContext::new(
+            template_root.path().to_path_buf(),
+            output_root.path().join("out"),
+            config,
+            Vec::new(),
+            false,
+            false,
+            None,
+        );
+        context.set_jobs(std::num::NonZeroUsize::new(4).unwrap());
+        context.set_answers(json!({"name": "baker"}));
+        let processor = TemplateProcessor::new(&engine, &context, &bakerignore).unwrap();
+
+        FileProcessor::new(processor, &context).process_all_files().unwrap();
+
+        for dir in 0..5 {
+            for file in 0..10 {
+                let target =
+                    output_root.path().join(format!("out/dir{dir}/file{file}.txt"));
+                assert_eq!(
+                    std::fs::read_to_string(target).unwrap(),
+                    format!("baker {dir}/{file}")
+                );
+            }
+        }
+    }
+
+    fn broken_template_context(
+        template_root: &TempDir,
+        output_parent: &TempDir,
+        on_error: OnError,
+    ) -> GenerationContext {
+        std::fs::write(template_root.path().join("good.txt.baker.j2"), "{{ name }}")
+            .unwrap();
+        std::fs::write(template_root.path().join("bad.txt.baker.j2"), "{% if %}")
+            .unwrap();
+        std::fs::write(template_root.path().join("worse.txt.baker.j2"), "{{ name | }}")
+            .unwrap();
+        let config: crate::config::ConfigV2 = serde_json::from_value(json!({})).unwrap();
+        let mut context = GenerationContext::new(
+            template_root.path().to_path_buf(),
+            output_parent.path().join("out"),
+            config,
+            Vec::new(),
+            false,
+            false,
+            None,
+        );
+        context.set_on_error(on_error);
+        context.set_answers(json!({"name": "baker"}));
+        context
+    }
+
+    #[test]
+    fn render_error_leaves_no_output_directory() {
+        let template_root = TempDir::new().unwrap();
+        let output_parent = TempDir::new().unwrap();
+        let context =
+            broken_template_context(&template_root, &output_parent, OnError::Fail);
+
+        let err =
+            process_template_layers(&MiniJinjaRenderer::new(), &context).unwrap_err();
+
+        assert!(
+            matches!(&err, Error::GenerationFailed { failures } if failures.len() == 2)
+        );
+        let message = err.to_string();
+        assert!(message.starts_with("2 file(s) failed to generate"), "{message}");
+        assert!(message.contains("bad.txt.baker.j2:1"), "{message}");
+        assert!(message.contains("worse.txt.baker.j2:1"), "{message}");
+        assert_eq!(std::fs::read_dir(output_parent.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn warn_policy_generates_the_other_files() {
+        let template_root = TempDir::new().unwrap();
+        let output_parent = TempDir::new().unwrap();
+        let context =
+            broken_template_context(&template_root, &output_parent, OnError::Warn);
+
+        let failures =
+            process_template_layers(&MiniJinjaRenderer::new(), &context).unwrap();
+
+        assert_eq!(failures.len(), 2);
+        let output_root = output_parent.path().join("out");
+        assert_eq!(
+            std::fs::read_to_string(output_root.join("good.txt")).unwrap(),
+            "baker"
+        );
+        assert!(!output_root.join("bad.txt").exists());
+        assert!(report_failures(OnError::Warn, &failures).is_err());
+        assert!(report_failures(OnError::Skip, &failures).is_ok());
+        assert!(report_failures(OnError::Warn, &[]).is_ok());
+    }
 }
@@ -0,0 +1,353 @@
+//! `baker questions` — exports a template's questionnaire as a JSON Schema for the
+//! answers, or as a commented YAML answers file to fill in and pass to
+//! `--answers-file`.
+
+use crate::{
+    cli::{QuestionsArgs, QuestionsFormat},
+    config::{
+        types::choice_text, Config, ConfigV2, IntoQuestionType, Question, QuestionType,
+        Validation,
+    },
+    error::Result,
+    loader::{extends::resolve_extends, get_template},
+};
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+/// Main entry point for `baker questions`.
+pub fn run_questions(args: QuestionsArgs) -> Result<()> {
+    let loaded = get_template(&args.template, false)?;
+    let mut config = Config::load(&loaded.root)?;
+    config.validate()?;
+    // Keep the temporary clones of git parents alive while their schema files are read.
+    let _chain = resolve_extends(&loaded.root, &mut config)?;
+
+    match args.format {
+        QuestionsFormat::JsonSchema => {
+            let schema = answers_json_schema(&config, &loaded.root, &args.template)?;
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+        }
+        QuestionsFormat::YamlSkeleton => {
+            print!("{}", answers_skeleton(&config, &args.template)?);
+        }
+    }
+    Ok(())
+}
+
+/// Builds a JSON Schema describing a valid answers document for the template.
+///
+/// Help texts become descriptions, choices become enums, and `ask_if` conditions
+/// are kept in the `x-baker-ask-if` extension keyword. Defaults that are templates
+/// depend on other answers and are exported as `x-baker-default` instead of `default`.
+pub fn answers_json_schema(
+    config: &ConfigV2,
+    template_root: &Path,
+    template_name: &str,
+) -> Result<Value> {
+    let mut properties = Map::new();
+    for (key, question) in &config.questions {
+        properties.insert(key.clone(), question_schema(question, template_root)?);
+    }
+    let required: Vec<&String> = config
+        .questions
+        .iter()
+        .filter(|(_, question)| question.required)
+        .map(|(key, _)| key)
+        .collect();
+
+    Ok(json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("Answers for {template_name}"),
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    }))
+}
+
+fn question_schema(question: &Question, template_root: &Path) -> Result<Value> {
+    let mut schema = match question.into_question_type() {
+        QuestionType::Text | QuestionType::Path => json!({ "type": "string" }),
+        QuestionType::Url => json!({ "type": "string", "format": "uri" }),
+        QuestionType::Date if question.format.is_none() => {
+            json!({ "type": "string", "format": "date" })
+        }
+        QuestionType::Date => json!({ "type": "string" }),
+        QuestionType::SingleChoice => choices_schema(question),
+        QuestionType::MultipleChoice => json!({
+            "type": "array",
+            "items": choices_schema(question),
+            "uniqueItems": true,
+        }),
+        QuestionType::Boolean => json!({ "type": "boolean" }),
+        QuestionType::Json | QuestionType::Yaml => {
+            let content = match &question.schema_file {
+                Some(schema_file) => {
+                    Some(std::fs::read_to_string(template_root.join(schema_file))?)
+                }
+                None => question.schema.clone(),
+            };
+            match content {
+                Some(content) => serde_json::from_str(&content)?,
+                None => json!({}),
+            }
+        }
+    };
+
+    if let Some(schema) = schema.as_object_mut() {
+        if !question.help.is_empty() {
+            schema.insert("description".to_string(), json!(question.help));
+        }
+        if is_templated(&question.default) {
+            schema.insert("x-baker-default".to_string(), question.default.clone());
+        } else if !question.default.is_null() {
+            schema.insert("default".to_string(), question.default.clone());
+        }
+        if !question.ask_if.is_empty() {
+            schema.insert("x-baker-ask-if".to_string(), json!(question.ask_if));
+        }
+        if question.secret.is_some() || question.secret_command.is_some() {
+            schema.insert("writeOnly".to_string(), json!(true));
+        }
+        let is_textual = matches!(
+            question.into_question_type(),
+            QuestionType::Text
+                | QuestionType::Path
+                | QuestionType::Url
+                | QuestionType::Date
+        );
+        if is_textual && !question.allow_empty {
+            schema.insert("minLength".to_string(), json!(1));
+        }
+        let is_list = question.into_question_type() == QuestionType::MultipleChoice;
+        let rules: Vec<Value> = question
+            .validation
+            .iter()
+            .map(|rule| builtin_rules_schema(rule, is_list))
+            .filter(|rule| rule.as_object().is_some_and(|rule| !rule.is_empty()))
+            .collect();
+        if !rules.is_empty() {
+            schema.insert("allOf".to_string(), json!(rules));
+        }
+    }
+    Ok(schema)
+}
+
+/// Translates the built-in validators of a rule into JSON Schema keywords.
+/// `condition` expressions have no JSON Schema equivalent and are left out.
+fn builtin_rules_schema(rule: &Validation, is_list: bool) -> Value {
+    let mut schema = Map::new();
+    let (min, max) =
+        if is_list { ("minItems", "maxItems") } else { ("minLength", "maxLength") };
+    if let Some(pattern) = &rule.regex {
+        schema.insert("pattern".to_string(), json!(pattern));
+    }
+    if let Some(min_length) = rule.min_length {
+        schema.insert(min.to_string(), json!(min_length));
+    }
+    if let Some(max_length) = rule.max_length {
+        schema.insert(max.to_string(), json!(max_length));
+    }
+    let mut values = Map::new();
+    if let Some(one_of) = &rule.one_of {
+        values.insert("enum".to_string(), json!(one_of));
+    }
+    if let Some(not_in) = &rule.not_in {
+        values.insert("not".to_string(), json!({ "enum": not_in }));
+    }
+    if is_list && !values.is_empty() {
+        schema.insert("items".to_string(), Value::Object(values));
+    } else {
+        schema.extend(values);
+    }
+    Value::Object(schema)
+}
+
+/// Builds a commented YAML answers file with one entry per question.
+///
+/// Each entry is preceded by the question's help text, type, choices and `ask_if`
+/// condition, and holds the default answer or an empty placeholder. Entries whose
+/// default is a template are commented out.
+pub fn answers_skeleton(config: &ConfigV2, template_name: &str) -> Result<String> {
+    let mut out = format!(
+        "# Answers for {template_name}\n# Fill in and pass with: baker generate --answers-file <this file>\n"
+    );
+    for (key, question) in &config.questions {
+        let question_type = question.into_question_type();
+        out.push('\n');
+        for line in question.help.lines() {
+            out.push_str(&format!("# {line}\n"));
+        }
+
+        let mut details = vec![format!("type: {}", type_label(&question_type))];
+        if question.required {
+            details.push("required".to_string());
+        }
+        if question.secret.is_some() || question.secret_command.is_some() {
+            details.push("secret".to_string());
+        }
+        out.push_str(&format!("# {}\n", details.join(", ")));
+        if !question.choices.is_empty() {
+            let choices: Vec<String> = question
+                .choices
+                .iter()
+                .map(|choice| choice_text(&choice.value))
+                .collect();
+            out.push_str(&format!("# choices: {}\n", choices.join(", ")));
+        }
+        if !question.ask_if.is_empty() {
+            out.push_str(&format!("# asked if: {}\n", question.ask_if));
+        }
+
+        let value = if question.default.is_null() || is_templated(&question.default) {
+            placeholder(&question_type, question)
+        } else {
+            question.default.clone()
+        };
+        let mut entry = Map::new();
+        entry.insert(key.clone(), value);
+        let entry = serde_yaml::to_string(&entry)?;
+
+        // A templated default is computed from the other answers, so leave the entry
+        // commented out to keep it.
+        if let Some(default) =
+            question.default.as_str().filter(|_| is_templated(&question.default))
+        {
+            out.push_str(&format!("# default: {default}\n"));
+            for line in entry.lines() {
+                out.push_str(&format!("# {line}\n"));
+            }
+        } else {
+            out.push_str(&entry);
+        }
+    }
+    Ok(out)
+}
+
+/// An enum of the choice values, typed as strings when every value is a string.
+fn choices_schema(question: &Question) -> Value {
+    let values: Vec<&Value> =
+        question.choices.iter().map(|choice| &choice.value).collect();
+    if values.iter().all(|value| value.is_string()) {
+        json!({ "type": "string", "enum": values })
+    } else {
+        json!({ "enum": values })
+    }
+}
+
+/// Whether a default is a template rendered against the other answers.
+fn is_templated(default: &Value) -> bool {
+    default.as_str().is_some_and(|s| s.contains("{{") || s.contains("{%"))
+}
+
+fn type_label(question_type: &QuestionType) -> &'static str {
+    match question_type {
+        QuestionType::Text => "string",
+        QuestionType::SingleChoice => "single choice",
+        QuestionType::MultipleChoice => "multiple choice",
+        QuestionType::Boolean => "boolean",
+        QuestionType::Json => "json",
+        QuestionType::Yaml => "yaml",
+        QuestionType::Path => "path",
+        QuestionType::Url => "url",
+        QuestionType::Date => "date",
+    }
+}
+
+/// An empty answer of the right shape for a question without a usable default.
+fn placeholder(question_type: &QuestionType, question: &Question) -> Value {
+    match question_type {
+        QuestionType::Text
+        | QuestionType::Path
+        | QuestionType::Url
+        | QuestionType::Date => json!(""),
+        QuestionType::SingleChoice => {
+            json!(question.choices.first().map(|choice| &choice.value))
+        }
+        QuestionType::MultipleChoice => json!([]),
+        QuestionType::Boolean => json!(false),
+        QuestionType::Json | QuestionType::Yaml => json!({}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ConfigV2 {
+        serde_yaml::from_str(
+            r#"
+questions:
+  project_name:
+    type: str
+    help: Project name
+    required: true
+  project_slug:
+    type: str
+    default: "{{ project_name | snake_case }}"
+  license:
+    type: str
+    choices: [MIT, Apache-2.0]
+    default: MIT
+  features:
+    type: str
+    choices: [docker, ci]
+    multiselect: true
+    validation:
+      max_length: 1
+      not_in: [ci]
+  use_docker:
+    type: bool
+    ask_if: "'docker' in features"
+  settings:
+    type: json
+    schema: '{"type": "object", "required": ["port"]}'
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn json_schema_describes_every_question() {
+        let schema = answers_json_schema(&config(), Path::new("."), "demo").unwrap();
+        let properties = &schema["properties"];
+
+        assert_eq!(schema["required"], json!(["project_name"]));
+        assert_eq!(
+            properties["project_name"],
+            json!({"type": "string", "description": "Project name"})
+        );
+        assert_eq!(
+            properties["project_slug"]["x-baker-default"],
+            "{{ project_name | snake_case }}"
+        );
+        assert_eq!(properties["license"]["enum"], json!(["MIT", "Apache-2.0"]));
+        assert_eq!(properties["license"]["default"], "MIT");
+        assert_eq!(properties["features"]["type"], "array");
+        assert_eq!(
+            properties["features"]["allOf"],
+            json!([{"maxItems": 1, "items": {"not": {"enum": ["ci"]}}}])
+        );
+        assert_eq!(properties["use_docker"]["x-baker-ask-if"], "'docker' in features");
+        assert_eq!(properties["settings"]["required"], json!(["port"]));
+    }
+
+    #[test]
+    fn yaml_skeleton_is_a_valid_commented_answers_file() {
+        let skeleton = answers_skeleton(&config(), "demo").unwrap();
+
+        assert!(skeleton
+            .contains("# Project name\n# type: string, required\nproject_name: ''\n"));
+        assert!(skeleton.contains(
+            "# default: {{ project_name | snake_case }}\n# project_slug: ''\n"
+        ));
+        assert!(
+            skeleton.contains("# asked if: 'docker' in features\nuse_docker: false\n")
+        );
+
+        let answers: Value = serde_yaml::from_str(&skeleton).unwrap();
+        assert!(answers.get("project_slug").is_none());
+        assert_eq!(answers["license"], "MIT");
+        assert_eq!(answers["features"], json!([]));
+        assert_eq!(answers["settings"], json!({}));
+    }
+}
@@ -1,25 +1,51 @@
 use crate::{
     cli::{
-        answers::AnswerCollector, context::GenerationContext, hooks::run_hook,
-        processor::FileProcessor, GenerateArgs, SkipConfirm,
+        answers::AnswerCollector,
+        context::{default_jobs, GenerationContext},
+        hooks::run_hook,
+        processor::{is_failed_generation, process_template_layers, report_failures},
+        GenerateArgs, SkipConfirm,
     },
-    config::{Config, ConfigV1},
+    config::{cookiecutter::Cookiecutter, Config, ConfigV2},
     error::{Error, Result},
     generated,
-    ignore::parse_bakerignore_file,
-    loader::get_template,
+    loader::{
+        extends::{resolve_extends, TemplateChain},
+        get_template,
+    },
     prompt::confirm,
     renderer::TemplateRenderer,
-    template::{get_template_engine, processor::TemplateProcessor},
+    replay,
+    template::get_template_engine_for,
+    user_config::UserConfig,
 };
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use log::debug;
 use serde_json::json;
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
-use walkdir::WalkDir;
+use std::path::{Path, PathBuf};
+
+/// Registers the `globals` and `prelude` templates of `config` with `engine`. Prelude
+/// files are named by their path relative to `import_root`, like imported templates.
+pub fn add_prelude_and_globals(
+    import_root: &Path,
+    config: &ConfigV2,
+    engine: &mut dyn TemplateRenderer,
+) -> Result<()> {
+    for (name, value) in &config.settings.globals {
+        engine.add_global(name, value.clone());
+    }
+    for name in &config.settings.prelude {
+        let path = import_root.join(name);
+        debug!("Adding prelude template: {}", path.display());
+        let template =
+            std::fs::read_to_string(&path).map_err(|e| Error::ProcessError {
+                source_path: path.display().to_string(),
+                e: format!("cannot read prelude template: {e}"),
+            })?;
+        engine.add_prelude(name, &template)?;
+    }
+    Ok(())
+}
 
 /// Main CLI runner that orchestrates the entire template generation workflow
 pub struct Runner {
@@ -33,43 +59,55 @@ impl Runner {
 
     /// Executes the complete template generation workflow
     pub fn run(self) -> Result<()> {
-        let mut engine = get_template_engine();
-        let (mut context, source_info) = self.prepare_environment(&mut engine)?;
+        let (mut context, source_info, chain) = self.prepare_environment()?;
+        let mut engine = self.prepare_engine(&context, &chain)?;
 
-        let hook_plan = self.prepare_hooks(&context, &engine)?;
+        let hook_plan = self.prepare_hooks(&context, &*engine)?;
 
-        let pre_hook_output = self.maybe_run_pre_hook(&hook_plan, &context, &engine)?;
+        let pre_hook_output = self.maybe_run_pre_hook(&hook_plan, &context, &*engine)?;
 
-        let answers = self.gather_answers(
+        let (answers, original_answers) = self.gather_answers(
             context.config(),
-            &engine,
+            &*engine,
             pre_hook_output,
             context.template_root(),
+            &source_info,
         )?;
         context.set_answers(answers);
+        self.save_replay(&context, &original_answers, &source_info);
 
-        self.process_templates(&context, &engine)?;
+        // Only files and paths are strict: questions may still refer to the answers
+        // of questions that were not asked.
+        engine.set_strict_undefined(
+            self.args.strict_undefined || context.config().settings.strict_undefined,
+        );
+        engine.set_random_seed(context.random_seed());
+        let failures = process_template_layers(&*engine, &context)?;
 
-        self.maybe_run_post_hook(&hook_plan, &context, &engine)?;
+        if is_failed_generation(context.on_error(), &failures) {
+            log::warn!("Not running the post hook, as the generation failed");
+        } else {
+            self.maybe_run_post_hook(&hook_plan, &context, &*engine)?;
+        }
 
-        self.finish(&context, source_info)?;
+        self.finish(&context, source_info, chain.sources(), &failures)?;
 
         Ok(())
     }
 
     fn prepare_environment(
         &self,
-        engine: &mut dyn TemplateRenderer,
-    ) -> Result<(GenerationContext, crate::loader::TemplateSourceInfo)> {
+    ) -> Result<(GenerationContext, crate::loader::TemplateSourceInfo, TemplateChain)>
+    {
         let output_root = self.prepare_output_dir()?;
         let loaded = self.resolve_template()?;
         let template_root = loaded.root;
         let source_info = loaded.source;
-        let config = self.load_and_validate_config(&template_root)?;
-        debug!("Loaded config: follow_symlinks={}", config.follow_symlinks);
-        self.add_templates_in_renderer(&template_root, &config, engine);
+        let mut config = self.load_and_validate_config(&template_root)?;
+        debug!("Loaded config: follow_symlinks={}", config.settings.follow_symlinks);
+        let chain = resolve_extends(&template_root, &mut config)?;
 
-        let ctx = GenerationContext::new(
+        let mut ctx = GenerationContext::new(
             template_root,
             output_root,
             config,
@@ -78,7 +116,38 @@ impl Runner {
             false,
             None,
         );
-        Ok((ctx, source_info))
+        ctx.set_parent_roots(chain.roots());
+        ctx.set_jobs(self.args.jobs.unwrap_or_else(default_jobs));
+        ctx.set_on_error(self.args.on_error);
+        if let Some(random_seed) = self.args.random_seed {
+            ctx.set_random_seed(random_seed);
+        }
+        Ok((ctx, source_info, chain))
+    }
+
+    /// Creates the template engine selected by the template and registers the
+    /// imports, preludes and globals of every layer with it.
+    fn prepare_engine(
+        &self,
+        context: &GenerationContext,
+        chain: &TemplateChain,
+    ) -> Result<Box<dyn TemplateRenderer>> {
+        let config = context.config();
+        let mut engine = get_template_engine_for(config.settings.engine)?;
+        engine.set_delimiters(&config.settings.delimiters)?;
+        if config.cookiecutter.is_some() {
+            engine.set_context_alias(Cookiecutter::CONTEXT_ALIAS);
+        }
+        // Register base-most imports first so that descendants override them.
+        for parent in chain.parents.iter().rev() {
+            self.add_templates_in_renderer(
+                &parent.root,
+                &parent.config,
+                engine.as_mut(),
+            )?;
+        }
+        self.add_templates_in_renderer(context.template_root(), config, engine.as_mut())?;
+        Ok(engine)
     }
 
     fn prepare_output_dir(&self) -> Result<PathBuf> {
@@ -93,9 +162,8 @@ impl Runner {
     fn load_and_validate_config(
         &self,
         template_root: &PathBuf,
-    ) -> Result<crate::config::ConfigV1> {
-        let config = Config::load_config(template_root)?;
-        let Config::V1(config) = config;
+    ) -> Result<crate::config::ConfigV2> {
+        let config = Config::load(template_root)?;
         config.validate()?;
         Ok(config)
     }
@@ -107,18 +175,18 @@ impl Runner {
     ) -> Result<HookPlan> {
         let config = context.config();
         let pre_hook_filename = engine.render(
-            &config.pre_hook_filename,
+            &config.hooks.pre.filename,
             &json!({}),
-            Some(&config.pre_hook_filename),
+            Some(&config.hooks.pre.filename),
         )?;
         let post_hook_filename = engine.render(
-            &config.post_hook_filename,
+            &config.hooks.post.filename,
             &json!({}),
-            Some(&config.post_hook_filename),
+            Some(&config.hooks.post.filename),
         )?;
-        let pre_hook_runner = config.pre_hook_runner.clone();
-        let post_hook_runner = config.post_hook_runner.clone();
-        let post_hook_print_stdout = config.post_hook_print_stdout;
+        let pre_hook_runner = config.hooks.pre.runner.clone();
+        let post_hook_runner = config.hooks.post.runner.clone();
+        let post_hook_print_stdout = config.hooks.post.print_stdout;
 
         let execute_hooks = self.confirm_hook_execution(
             context.template_root(),
@@ -185,34 +253,67 @@ impl Runner {
         }
     }
 
-    /// Collects answers from all available sources
+    /// Collects answers from all available sources. Also returns the answers as given,
+    /// before any `transform`, which is what `--replay` stores.
     fn gather_answers(
         &self,
-        config: &crate::config::ConfigV1,
+        config: &crate::config::ConfigV2,
         engine: &dyn crate::renderer::TemplateRenderer,
         pre_hook_output: Option<String>,
         template_root: &Path,
-    ) -> Result<serde_json::Value> {
+        source_info: &crate::loader::TemplateSourceInfo,
+    ) -> Result<(serde_json::Value, serde_json::Value)> {
+        let mut default_answers = if self.args.no_user_config {
+            serde_json::Map::new()
+        } else {
+            UserConfig::load()?.answers_for(&self.args.template, source_info)
+        };
+        if self.args.replay {
+            let saved = match replay::default_dir() {
+                Some(dir) => replay::load(&dir, source_info)?,
+                None => None,
+            };
+            let saved = saved.ok_or_else(|| Error::ReplayNotFound {
+                template: source_info.location().to_string(),
+            })?;
+            log::debug!("Replaying answers saved at {}", saved.saved_at);
+            if let serde_json::Value::Object(answers) = saved.answers {
+                default_answers.extend(answers);
+            }
+        }
         let collector =
-            AnswerCollector::new(engine, self.args.non_interactive, template_root);
-        collector.collect_answers(
+            AnswerCollector::new(engine, self.args.non_interactive, template_root)
+                .with_strict(self.args.strict)
+                .with_skip_command_prompts(self.should_skip_hook_prompts())
+                .with_default_answers(default_answers)
+                .with_answers_format(self.args.answers_format);
+        collector.collect_answers_and_originals(
             config,
             pre_hook_output,
             self.args.answers.clone(),
             self.args.answers_file.clone(),
         )
     }
-    fn process_templates(
+
+    /// Records the answers for `--replay` before any file is generated, so that they
+    /// survive a failed run. The answers are stored as given, since replaying them runs
+    /// the questions' transforms again. Failures are logged but never abort generation.
+    fn save_replay(
         &self,
         context: &GenerationContext,
-        engine: &dyn crate::renderer::TemplateRenderer,
-    ) -> Result<()> {
-        let bakerignore = parse_bakerignore_file(context.template_root())?;
-
-        let processor = TemplateProcessor::new(engine, context, &bakerignore);
-
-        let file_processor = FileProcessor::new(processor, context);
-        file_processor.process_all_files()
+        original_answers: &serde_json::Value,
+        source_info: &crate::loader::TemplateSourceInfo,
+    ) {
+        if self.args.no_replay || context.dry_run() {
+            return;
+        }
+        let Some(dir) = replay::default_dir() else {
+            return;
+        };
+        let answers = generated::strip_secret_answers(original_answers, context.config());
+        if let Err(e) = replay::save(&dir, source_info, &answers) {
+            log::warn!("Failed to save answers for --replay: {e}");
+        }
     }
 
     fn maybe_run_post_hook(
@@ -257,10 +358,12 @@ impl Runner {
         &self,
         context: &GenerationContext,
         source_info: crate::loader::TemplateSourceInfo,
+        extends: Vec<crate::loader::TemplateSourceInfo>,
+        failures: &[Error],
     ) -> Result<()> {
         let file_name = generated::resolve_file_name(
             self.args.generated_file.as_deref(),
-            context.config().generated_file_name.as_deref(),
+            context.config().settings.generated_file_name.as_deref(),
         );
 
         if context.dry_run() {
@@ -271,10 +374,14 @@ impl Runner {
         } else {
             let answers =
                 generated::strip_secret_answers(context.answers(), context.config());
-            let data = generated::BakerGenerated::new(source_info, answers);
+            let data = generated::BakerGenerated::new(source_info, answers)
+                .with_extends(extends)
+                .with_random_seed(self.args.random_seed)
+                .with_failures(failures);
             generated::write(context.output_root(), file_name, &data)?;
         }
 
+        report_failures(context.on_error(), failures)?;
         println!("{}", completion_message(context.dry_run(), context.output_root()));
         Ok(())
     }
@@ -310,29 +417,28 @@ impl Runner {
         Ok(output_dir.to_path_buf())
     }
 
-    /// Adds template files from a directory into a MiniJinja renderer, using multiple glob patterns.
+    /// Makes the template files of a directory available to `import` and `include`, using
+    /// multiple glob patterns.
     ///
-    /// This function scans the `template_root` directory recursively and adds all files matching
-    /// any of the glob patterns specified in `config.template_globs` to the provided
-    /// template engine. This allows for flexible inclusion of templates with different extensions
-    /// or naming conventions.
+    /// Files matching any of the glob patterns specified in `config.template_globs` can be
+    /// loaded by the template engine. This allows for flexible inclusion of templates with
+    /// different extensions or naming conventions. Nothing is read up front: the engine
+    /// loads and compiles a file the first time a template uses it.
     ///
-    /// If `config.import_root` is specified, it will be used as the base directory for scanning
-    /// template files. Otherwise, `template_root` is used.
+    /// If `config.import_root` is specified, it will be used as the base directory for
+    /// template names. Otherwise, `template_root` is used.
     ///
     /// # Arguments
     /// * `template_root` - The root directory containing template files.
     /// * `config` - The configuration object specifying glob patterns and optional import root.
-    /// * `engine` - The template renderer to which the templates will be added.
-    ///
-    /// Only files matching at least one of the provided patterns will be processed and added.
+    /// * `engine` - The template renderer the directory is registered with.
     fn add_templates_in_renderer(
         &self,
         template_root: &Path,
-        config: &ConfigV1,
+        config: &ConfigV2,
         engine: &mut dyn TemplateRenderer,
-    ) {
-        let import_root = if let Some(ref import_root_str) = config.import_root {
+    ) -> Result<()> {
+        let import_root = if let Some(ref import_root_str) = config.settings.import_root {
             let import_path = Path::new(import_root_str);
             if import_path.is_absolute() {
                 import_path.to_path_buf()
@@ -345,33 +451,21 @@ impl Runner {
 
         debug!("Using import root: {}", import_root.display());
 
-        let templates_import_globset =
-            self.build_templates_import_globset(&import_root, &config.template_globs);
+        let templates_import_globset = self.build_templates_import_globset(
+            &import_root,
+            &config.settings.template_globs,
+        );
 
         if let Some(globset) = templates_import_globset {
-            debug!("Adding templates from glob patterns: {:?}", &config.template_globs);
-            WalkDir::new(&import_root)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|entry| entry.path().is_file())
-                .filter(|entry| globset.is_match(entry.path()))
-                .filter_map(|entry| {
-                    let path = entry.path();
-                    let rel_path = path.strip_prefix(&import_root).ok()?;
-                    let rel_path_str = rel_path.to_str()?;
-                    fs::read_to_string(path)
-                        .ok()
-                        .map(|content| (rel_path_str.to_owned(), content))
-                })
-                .for_each(|(filename, content)| {
-                    debug!("Adding template: {filename}");
-                    if let Err(e) = engine.add_template(&filename, &content) {
-                        log::warn!("Failed to add template {filename}: {e}");
-                    }
-                });
+            debug!(
+                "Adding templates from glob patterns: {:?}",
+                &config.settings.template_globs
+            );
+            engine.add_template_dir(&import_root, globset);
         } else {
             debug!("template_globs is empty. No patterns provided for adding templates in the template engine for import and include.");
         }
+        add_prelude_and_globals(&import_root, config, engine)
     }
 
     /// Constructs a `GlobSet` for matching template files using multiple patterns relative to a root directory.
@@ -397,8 +491,9 @@ impl Runner {
             return None;
         }
         let mut builder = GlobSetBuilder::new();
+        let escaped_root = globset::escape(&template_root.to_string_lossy());
         for pattern in patterns {
-            let path_to_ignored_pattern = template_root.join(pattern);
+            let path_to_ignored_pattern = Path::new(&escaped_root).join(pattern);
             let path_str = path_to_ignored_pattern.display().to_string();
             if let Ok(glob) = Glob::new(&path_str) {
                 builder.add(glob);
@@ -536,8 +631,17 @@ mod tests {
             force: false,
             answers: None,
             answers_file: None,
+            answers_format: Default::default(),
+            jobs: None,
+            on_error: Default::default(),
+            strict_undefined: false,
+            random_seed: None,
             skip_confirms: Vec::new(),
             non_interactive: false,
+            strict: false,
+            no_user_config: true,
+            replay: false,
+            no_replay: true,
             dry_run: false,
             generated_file: None,
             conflict_style: None,
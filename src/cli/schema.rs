@@ -0,0 +1,9 @@
+//! `baker schema` — prints the JSON Schema for `baker.yaml`.
+
+use crate::{config::schema::config_schema, error::Result};
+
+/// Main entry point for `baker schema`.
+pub fn run_schema() -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&config_schema())?);
+    Ok(())
+}
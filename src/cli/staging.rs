@@ -0,0 +1,284 @@
+//! Staging of generated files, so that a failed generation never leaves a partially
+//! written output directory behind.
+
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+/// A temporary directory next to the output directory that receives every generated
+/// file. Nothing touches the output directory until [`StagingDir::commit`]; dropping
+/// the staging directory without committing discards it.
+pub struct StagingDir {
+    dir: TempDir,
+    /// The output directory as the generated targets refer to it.
+    output_root: PathBuf,
+    /// The canonical output directory the staged files are moved into.
+    resolved_root: PathBuf,
+}
+
+impl StagingDir {
+    /// Creates the staging directory in the parent of `output_root`, so that its
+    /// entries can be renamed into place. The parent is taken from the canonical
+    /// output directory, so that `-o .` stages next to the current directory
+    /// instead of inside it.
+    pub fn new(output_root: &Path) -> Result<Self> {
+        let resolved_root = resolve_output_root(output_root)?;
+        let parent = resolved_root.parent().ok_or_else(|| Error::ProcessError {
+            source_path: resolved_root.display().to_string(),
+            e: "the output directory has no parent directory to stage files in"
+                .to_string(),
+        })?;
+        let name =
+            resolved_root.file_name().and_then(|name| name.to_str()).unwrap_or("output");
+        let dir = tempfile::Builder::new()
+            .prefix(&format!(".{name}.baker-staging-"))
+            .tempdir_in(parent)?;
+        Ok(Self { dir, output_root: output_root.to_path_buf(), resolved_root })
+    }
+
+    /// Returns where `target`, a path in the output directory, is staged. Paths
+    /// outside the output directory are an error, as writing them directly could
+    /// not be rolled back.
+    pub fn path_for(&self, target: &Path) -> Result<PathBuf> {
+        match target.strip_prefix(&self.output_root) {
+            Ok(relative) => Ok(self.dir.path().join(relative)),
+            Err(_) => Err(Error::ProcessError {
+                source_path: target.display().to_string(),
+                e: format!(
+                    "the path is outside the output directory '{}'",
+                    self.output_root.display()
+                ),
+            }),
+        }
+    }
+
+    /// Moves the staged files into the output directory.
+    ///
+    /// A missing output directory is replaced by the staging directory in a single
+    /// rename. Otherwise the staged entries are moved over the existing ones one by
+    /// one, and every change is rolled back if one of them fails.
+    pub fn commit(self) -> Result<()> {
+        if fs::symlink_metadata(&self.resolved_root).is_err() {
+            fs::rename(self.dir.path(), &self.resolved_root)?;
+            return Ok(());
+        }
+
+        let backup = tempfile::Builder::new()
+            .prefix(".baker-backup-")
+            .tempdir_in(self.dir.path().parent().unwrap_or(Path::new(".")))?;
+        let mut journal = Vec::new();
+        let result = self.merge(backup.path(), &mut journal);
+        if result.is_err() {
+            rollback(journal);
+        }
+        result
+    }
+
+    /// Moves every staged entry into the output directory, recording each change in
+    /// `journal`. Replaced files are moved into `backup`.
+    fn merge(&self, backup: &Path, journal: &mut Vec<Change>) -> Result<()> {
+        for entry in WalkDir::new(self.dir.path()).min_depth(1).sort_by_file_name() {
+            let entry = entry?;
+            let relative = entry.path().strip_prefix(self.dir.path()).map_err(|e| {
+                Error::ProcessError {
+                    source_path: entry.path().display().to_string(),
+                    e: e.to_string(),
+                }
+            })?;
+            let target = self.resolved_root.join(relative);
+            let existing = fs::symlink_metadata(&target).ok();
+
+            if entry.file_type().is_dir() {
+                match existing {
+                    Some(metadata) if metadata.is_dir() => {}
+                    Some(_) => {
+                        return Err(Error::ProcessError {
+                            source_path: target.display().to_string(),
+                            e: "a file with this name already exists".to_string(),
+                        })
+                    }
+                    None => {
+                        fs::create_dir(&target)?;
+                        journal.push(Change::CreatedDir(target));
+                    }
+                }
+                continue;
+            }
+
+            let replaced = match existing {
+                Some(metadata) if metadata.is_dir() => {
+                    return Err(Error::ProcessError {
+                        source_path: target.display().to_string(),
+                        e: "a directory with this name already exists".to_string(),
+                    })
+                }
+                Some(_) => {
+                    let replaced = backup.join(journal.len().to_string());
+                    fs::rename(&target, &replaced)?;
+                    Some(replaced)
+                }
+                None => None,
+            };
+            let moved = fs::rename(entry.path(), &target);
+            journal.push(Change::MovedFile { target, replaced });
+            moved?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the canonical path of `output_root`. A missing output directory is
+/// resolved through its parent, which is created.
+fn resolve_output_root(output_root: &Path) -> Result<PathBuf> {
+    if output_root.exists() {
+        return Ok(fs::canonicalize(output_root)?);
+    }
+    let parent = output_root
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    let name = output_root.file_name().ok_or_else(|| Error::ProcessError {
+        source_path: output_root.display().to_string(),
+        e: "the output directory has no name".to_string(),
+    })?;
+    Ok(fs::canonicalize(parent)?.join(name))
+}
+
+/// A change made to the output directory while committing.
+enum Change {
+    CreatedDir(PathBuf),
+    /// `target` was written; the file it replaced, if any, was moved to `replaced`.
+    MovedFile {
+        target: PathBuf,
+        replaced: Option<PathBuf>,
+    },
+}
+
+/// Undoes `journal`, newest change first. Failures are logged, as there is nothing
+/// else left to do about them.
+fn rollback(journal: Vec<Change>) {
+    for change in journal.into_iter().rev() {
+        let result = match &change {
+            Change::CreatedDir(target) => fs::remove_dir(target),
+            Change::MovedFile { target, replaced } => {
+                let removed = match fs::remove_file(target) {
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                    other => other,
+                };
+                match replaced {
+                    Some(replaced) => removed.and_then(|_| fs::rename(replaced, target)),
+                    None => removed,
+                }
+            }
+        };
+        if let Err(e) = result {
+            log::error!("Failed to roll back the output directory: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_moves_staging_into_missing_output_directory() {
+        let parent = tempfile::tempdir().unwrap();
+        let output_root = parent.path().join("project");
+        let staging = StagingDir::new(&output_root).unwrap();
+        let staged = staging.path_for(&output_root.join("src/main.rs")).unwrap();
+        fs::create_dir_all(staged.parent().unwrap()).unwrap();
+        fs::write(&staged, "fn main() {}").unwrap();
+        assert!(!output_root.exists());
+
+        staging.commit().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(output_root.join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn commit_merges_into_existing_output_directory() {
+        let parent = tempfile::tempdir().unwrap();
+        let output_root = parent.path().join("project");
+        fs::create_dir(&output_root).unwrap();
+        fs::write(output_root.join("README.md"), "old").unwrap();
+        fs::write(output_root.join("notes.txt"), "kept").unwrap();
+        let staging = StagingDir::new(&output_root).unwrap();
+        fs::write(staging.path_for(&output_root.join("README.md")).unwrap(), "new")
+            .unwrap();
+
+        staging.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(output_root.join("README.md")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(output_root.join("notes.txt")).unwrap(), "kept");
+        assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn failed_commit_restores_the_output_directory() {
+        let parent = tempfile::tempdir().unwrap();
+        let output_root = parent.path().join("project");
+        fs::create_dir(&output_root).unwrap();
+        fs::write(output_root.join("a.txt"), "old a").unwrap();
+        // A file where the staging directory has a directory cannot be merged.
+        fs::write(output_root.join("z"), "file").unwrap();
+        let staging = StagingDir::new(&output_root).unwrap();
+        fs::write(staging.path_for(&output_root.join("a.txt")).unwrap(), "new a")
+            .unwrap();
+        fs::create_dir(staging.path_for(&output_root.join("new_dir")).unwrap()).unwrap();
+        fs::create_dir(staging.path_for(&output_root.join("z")).unwrap()).unwrap();
+
+        assert!(staging.commit().is_err());
+
+        assert_eq!(fs::read_to_string(output_root.join("a.txt")).unwrap(), "old a");
+        assert!(!output_root.join("new_dir").exists());
+        assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn dropping_discards_staged_files() {
+        let parent = tempfile::tempdir().unwrap();
+        let output_root = parent.path().join("project");
+        let staging = StagingDir::new(&output_root).unwrap();
+        fs::write(staging.path_for(&output_root.join("file.txt")).unwrap(), "content")
+            .unwrap();
+
+        drop(staging);
+
+        assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn stages_outside_an_output_directory_without_a_file_name() {
+        let parent = tempfile::tempdir().unwrap();
+        let nested = parent.path().join("project");
+        fs::create_dir(&nested).unwrap();
+        // Like `-o .`, `project/..` names the output directory only through its path
+        let output_root = nested.join("..");
+        let staging = StagingDir::new(&output_root).unwrap();
+
+        let resolved_root = fs::canonicalize(parent.path()).unwrap();
+        assert!(!staging.dir.path().starts_with(&resolved_root));
+        fs::write(staging.path_for(&output_root.join("file.txt")).unwrap(), "new")
+            .unwrap();
+        staging.commit().unwrap();
+        assert_eq!(fs::read_to_string(parent.path().join("file.txt")).unwrap(), "new");
+        assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn targets_outside_the_output_directory_are_rejected() {
+        let parent = tempfile::tempdir().unwrap();
+        let output_root = parent.path().join("project");
+        let staging = StagingDir::new(&output_root).unwrap();
+
+        assert!(staging.path_for(&parent.path().join("elsewhere.txt")).is_err());
+    }
+}
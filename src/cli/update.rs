@@ -3,23 +3,26 @@
 
 use crate::{
     cli::{
-        answers::AnswerCollector, context::GenerationContext, hooks::run_hook,
-        processor::FileProcessor, UpdateArgs,
+        answers::{load_answers_file, AnswerCollector},
+        context::{default_jobs, GenerationContext},
+        hooks::run_hook,
+        processor::{is_failed_generation, process_template_layers, report_failures},
+        runner::add_prelude_and_globals,
+        UpdateArgs,
     },
-    config::{Config, ConfigV1},
+    config::{cookiecutter::Cookiecutter, Config, ConfigV2},
     conflict::ConflictStyle,
     error::Result,
     generated::{self, BakerGenerated},
-    ignore::parse_bakerignore_file,
-    loader::{get_template, TemplateSourceInfo},
+    loader::{extends::resolve_extends, get_template, TemplateSourceInfo},
     renderer::TemplateRenderer,
-    template::{get_template_engine, processor::TemplateProcessor},
+    template::get_template_engine_for,
+    user_config::UserConfig,
 };
 use globset::{Glob, GlobSetBuilder};
 use serde_json::json;
 use std::path::{Path, PathBuf};
 use tempfile::TempDir;
-use walkdir::WalkDir;
 
 // Public entry point
 
@@ -70,17 +73,28 @@ impl UpdateRunner {
         let (loaded, _tmp_guard) =
             self.fetch_updated_template(&meta.template, skip_overwrite)?;
 
-        if self.sources_are_identical(&meta.template, &loaded.source) {
+        let mut config = load_and_validate_config(&loaded.root)?;
+        let chain = resolve_extends(&loaded.root, &mut config)?;
+
+        if !meta.failures.is_empty() {
+            log::warn!(
+                "The last generation left out {} file(s) that failed to generate",
+                meta.failures.len()
+            );
+        }
+        // An incomplete project is regenerated even if the template did not change
+        if meta.failures.is_empty()
+            && self.sources_are_identical(&meta.template, &loaded.source)
+            && self.chains_are_identical(&meta.extends, &chain.sources())
+        {
             println!("Template has not changed since last generation — nothing to do.");
             return Ok(());
         }
 
-        let merged_answers = self.merge_answers(meta.answers.clone())?;
-
-        let config = load_and_validate_config(&loaded.root)?;
+        let merged_answers = self.merge_answers(meta.answers.clone(), &config)?;
 
         let conflict_style: Option<ConflictStyle> =
-            self.args.conflict_style.or(config.conflict_marker_style);
+            self.args.conflict_style.or(config.settings.conflict_marker_style);
 
         let mut context = GenerationContext::new(
             loaded.root.clone(),
@@ -91,15 +105,29 @@ impl UpdateRunner {
             true, // conflict_mode
             conflict_style,
         );
+        context.set_parent_roots(chain.roots());
+        context.set_jobs(self.args.jobs.unwrap_or_else(default_jobs));
+        context.set_on_error(self.args.on_error);
+        let random_seed = self.args.random_seed.or(meta.random_seed);
+        if let Some(random_seed) = random_seed {
+            context.set_random_seed(random_seed);
+        }
         context.set_answers(merged_answers.clone());
 
-        let mut engine = get_template_engine();
-        add_templates_in_renderer(&loaded.root, context.config(), &mut engine);
+        let mut engine = get_template_engine_for(context.config().settings.engine)?;
+        engine.set_delimiters(&context.config().settings.delimiters)?;
+        if context.config().cookiecutter.is_some() {
+            engine.set_context_alias(Cookiecutter::CONTEXT_ALIAS);
+        }
+        for parent in chain.parents.iter().rev() {
+            add_templates_in_renderer(&parent.root, &parent.config, engine.as_mut())?;
+        }
+        add_templates_in_renderer(&loaded.root, context.config(), engine.as_mut())?;
 
-        let execute_hooks = self.confirm_hooks(&context, &engine)?;
+        let execute_hooks = self.confirm_hooks(&context, &*engine)?;
 
         let pre_hook_output =
-            self.maybe_run_pre_hook(&context, &engine, execute_hooks)?;
+            self.maybe_run_pre_hook(&context, &*engine, execute_hooks)?;
 
         if let Some(ref hook_json) = pre_hook_output {
             if let Ok(hook_val) = serde_json::from_str::<serde_json::Value>(hook_json) {
@@ -114,8 +142,16 @@ impl UpdateRunner {
         }
 
         let merged_json_str = serde_json::to_string(context.answers())?;
+        let default_answers = if self.args.no_user_config {
+            serde_json::Map::new()
+        } else {
+            UserConfig::load()?.answers_for(loaded.source.location(), &loaded.source)
+        };
         let collector =
-            AnswerCollector::new(&engine, self.args.non_interactive, &loaded.root);
+            AnswerCollector::new(&*engine, self.args.non_interactive, &loaded.root)
+                .with_strict(self.args.strict)
+                .with_skip_command_prompts(self.should_skip_hook_prompts())
+                .with_default_answers(default_answers);
         let final_answers = collector.collect_answers(
             context.config(),
             pre_hook_output,
@@ -124,12 +160,19 @@ impl UpdateRunner {
         )?;
         context.set_answers(final_answers);
 
-        let bakerignore = parse_bakerignore_file(context.template_root())?;
-        let processor = TemplateProcessor::new(&engine, &context, &bakerignore);
-        let file_processor = FileProcessor::new(processor, &context);
-        file_processor.process_all_files()?;
+        // Only files and paths are strict: questions may still refer to the answers
+        // of questions that were not asked.
+        engine.set_strict_undefined(
+            self.args.strict_undefined || context.config().settings.strict_undefined,
+        );
+        engine.set_random_seed(context.random_seed());
+        let failures = process_template_layers(&*engine, &context)?;
 
-        self.maybe_run_post_hook(&context, &engine, execute_hooks)?;
+        if is_failed_generation(context.on_error(), &failures) {
+            log::warn!("Not running the post hook, as the update failed");
+        } else {
+            self.maybe_run_post_hook(&context, &*engine, execute_hooks)?;
+        }
 
         if context.dry_run() {
             log::info!(
@@ -139,9 +182,13 @@ impl UpdateRunner {
         } else {
             let answers =
                 generated::strip_secret_answers(context.answers(), context.config());
-            let new_meta = BakerGenerated::new(loaded.source, answers);
+            let new_meta = BakerGenerated::new(loaded.source, answers)
+                .with_extends(chain.sources())
+                .with_random_seed(random_seed)
+                .with_failures(&failures);
             generated::write(&cwd, file_name, &new_meta)?;
         }
+        report_failures(context.on_error(), &failures)?;
 
         println!(
             "{}",
@@ -198,20 +245,32 @@ impl UpdateRunner {
         }
     }
 
+    /// Compares the recorded `extends` chain with the freshly resolved one.
+    fn chains_are_identical(
+        &self,
+        stored: &[TemplateSourceInfo],
+        fresh: &[TemplateSourceInfo],
+    ) -> bool {
+        stored.len() == fresh.len()
+            && stored
+                .iter()
+                .zip(fresh)
+                .all(|(old, new)| self.sources_are_identical(old, new))
+    }
+
     /// Merges saved answers with CLI overrides (--answers-file, then --answers).
-    fn merge_answers(&self, saved: serde_json::Value) -> Result<serde_json::Value> {
+    fn merge_answers(
+        &self,
+        saved: serde_json::Value,
+        config: &ConfigV2,
+    ) -> Result<serde_json::Value> {
         let mut base = match saved {
             serde_json::Value::Object(m) => m,
             _ => serde_json::Map::new(),
         };
 
         if let Some(ref path) = self.args.answers_file {
-            let content = std::fs::read_to_string(path)?;
-            let parsed: serde_json::Value = serde_json::from_str(&content)?;
-            match parsed {
-                serde_json::Value::Object(extra) => base.extend(extra),
-                _ => return Err(crate::error::Error::AnswersNotObject),
-            }
+            base.extend(load_answers_file(path, self.args.answers_format, config)?);
         }
 
         if let Some(ref answers_str) = self.args.answers {
@@ -253,11 +312,11 @@ impl UpdateRunner {
         let config = context.config();
         let pre_hook_filename = engine
             .render(
-                &config.pre_hook_filename,
+                &config.hooks.pre.filename,
                 &json!({}),
-                Some(&config.pre_hook_filename),
+                Some(&config.hooks.pre.filename),
             )
-            .unwrap_or_else(|_| config.pre_hook_filename.clone());
+            .unwrap_or_else(|_| config.hooks.pre.filename.clone());
 
         let pre_hook_file =
             context.template_root().join("hooks").join(&pre_hook_filename);
@@ -274,7 +333,7 @@ impl UpdateRunner {
         if execute_hooks {
             let runner = render_hook_runner(
                 engine,
-                &config.pre_hook_runner,
+                &config.hooks.pre.runner,
                 context.answers_opt(),
             )?;
             run_hook(
@@ -299,11 +358,11 @@ impl UpdateRunner {
         let config = context.config();
         let post_hook_filename = engine
             .render(
-                &config.post_hook_filename,
+                &config.hooks.post.filename,
                 &json!({}),
-                Some(&config.post_hook_filename),
+                Some(&config.hooks.post.filename),
             )
-            .unwrap_or_else(|_| config.post_hook_filename.clone());
+            .unwrap_or_else(|_| config.hooks.post.filename.clone());
 
         let post_hook_file =
             context.template_root().join("hooks").join(&post_hook_filename);
@@ -320,7 +379,7 @@ impl UpdateRunner {
         if execute_hooks {
             let runner = render_hook_runner(
                 engine,
-                &config.post_hook_runner,
+                &config.hooks.post.runner,
                 context.answers_opt(),
             )?;
             run_hook(
@@ -329,7 +388,7 @@ impl UpdateRunner {
                 &post_hook_file,
                 Some(context.answers()),
                 &runner,
-                config.post_hook_print_stdout,
+                config.hooks.post.print_stdout,
             )?;
         }
         Ok(())
@@ -344,18 +403,18 @@ impl UpdateRunner {
         let config = context.config();
         let pre_hook_filename = engine
             .render(
-                &config.pre_hook_filename,
+                &config.hooks.pre.filename,
                 &json!({}),
-                Some(&config.pre_hook_filename),
+                Some(&config.hooks.pre.filename),
             )
-            .unwrap_or_else(|_| config.pre_hook_filename.clone());
+            .unwrap_or_else(|_| config.hooks.pre.filename.clone());
         let post_hook_filename = engine
             .render(
-                &config.post_hook_filename,
+                &config.hooks.post.filename,
                 &json!({}),
-                Some(&config.post_hook_filename),
+                Some(&config.hooks.post.filename),
             )
-            .unwrap_or_else(|_| config.post_hook_filename.clone());
+            .unwrap_or_else(|_| config.hooks.post.filename.clone());
 
         let pre_hook_file =
             context.template_root().join("hooks").join(&pre_hook_filename);
@@ -389,9 +448,8 @@ impl UpdateRunner {
 
 // Standalone helpers
 
-fn load_and_validate_config(template_root: &PathBuf) -> Result<ConfigV1> {
-    let config = Config::load_config(template_root)?;
-    let Config::V1(config) = config;
+fn load_and_validate_config(template_root: &PathBuf) -> Result<ConfigV2> {
+    let config = Config::load(template_root)?;
     config.validate()?;
     Ok(config)
 }
@@ -412,10 +470,10 @@ fn render_hook_runner(
 /// Add import templates from `template_root` to the engine (mirrors Runner::add_templates_in_renderer).
 fn add_templates_in_renderer(
     template_root: &Path,
-    config: &ConfigV1,
+    config: &ConfigV2,
     engine: &mut dyn TemplateRenderer,
-) {
-    let import_root = if let Some(ref s) = config.import_root {
+) -> Result<()> {
+    let import_root = if let Some(ref s) = config.settings.import_root {
         let p = Path::new(s);
         if p.is_absolute() {
             p.to_path_buf()
@@ -426,37 +484,21 @@ fn add_templates_in_renderer(
         template_root.to_path_buf()
     };
 
-    if config.template_globs.is_empty() {
-        return;
-    }
-
-    let mut builder = GlobSetBuilder::new();
-    for pattern in &config.template_globs {
-        let full = import_root.join(pattern);
-        if let Ok(g) = Glob::new(&full.to_string_lossy()) {
-            builder.add(g);
+    if !config.settings.template_globs.is_empty() {
+        let mut builder = GlobSetBuilder::new();
+        let escaped_root = globset::escape(&import_root.to_string_lossy());
+        for pattern in &config.settings.template_globs {
+            let full = Path::new(&escaped_root).join(pattern);
+            if let Ok(g) = Glob::new(&full.to_string_lossy()) {
+                builder.add(g);
+            }
+        }
+        if let Ok(globset) = builder.build() {
+            engine.add_template_dir(&import_root, globset);
         }
     }
-    let globset = match builder.build() {
-        Ok(gs) => gs,
-        Err(_) => return,
-    };
 
-    WalkDir::new(&import_root)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file() && globset.is_match(e.path()))
-        .filter_map(|e| {
-            let path = e.path();
-            let rel = path.strip_prefix(&import_root).ok()?;
-            let name = rel.to_str()?.to_owned();
-            std::fs::read_to_string(path).ok().map(|c| (name, c))
-        })
-        .for_each(|(name, content)| {
-            if let Err(e) = engine.add_template(&name, &content) {
-                log::warn!("Failed to add template {name}: {e}");
-            }
-        });
+    add_prelude_and_globals(&import_root, config, engine)
 }
 
 /// Clone a git repository into a sub-directory of `parent` and return its `LoadedTemplate`.
@@ -483,20 +525,25 @@ mod tests {
             generated_file: None,
             answers: None,
             answers_file: None,
+            answers_format: Default::default(),
+            jobs: None,
+            on_error: Default::default(),
+            strict_undefined: false,
+            random_seed: None,
             conflict_style: None,
             dry_run: false,
             skip_confirms: vec![],
             non_interactive: false,
+            strict: false,
+            no_user_config: true,
         }
     }
 
-    fn parse_config(raw: &str) -> ConfigV1 {
-        let config: Config = serde_yaml::from_str(raw).expect("valid config yaml");
-        let Config::V1(v1) = config;
-        v1
+    fn parse_config(raw: &str) -> ConfigV2 {
+        serde_yaml::from_str::<Config>(raw).expect("valid config yaml").normalize()
     }
 
-    fn minimal_config() -> ConfigV1 {
+    fn minimal_config() -> ConfigV2 {
         parse_config(
             r#"
 schemaVersion: v1
@@ -562,6 +609,20 @@ questions: {}
         assert!(!runner.sources_are_identical(&git_a, &fs_a));
     }
 
+    #[test]
+    fn chains_are_identical_compares_every_parent() {
+        let runner = UpdateRunner::new(default_update_args());
+        let parent = |hash: &str| TemplateSourceInfo::Filesystem {
+            path: "/tmp/base".to_string(),
+            hash: hash.to_string(),
+        };
+
+        assert!(runner.chains_are_identical(&[], &[]));
+        assert!(runner.chains_are_identical(&[parent("1")], &[parent("1")]));
+        assert!(!runner.chains_are_identical(&[parent("1")], &[parent("2")]));
+        assert!(!runner.chains_are_identical(&[], &[parent("1")]));
+    }
+
     #[test]
     fn merge_answers_merges_saved_file_and_inline_json() {
         let dir = tempdir().expect("create temp dir");
@@ -573,7 +634,9 @@ questions: {}
         args.answers = Some(r#"{"inline":3}"#.to_string());
 
         let runner = UpdateRunner::new(args);
-        let merged = runner.merge_answers(json!({"saved": 1})).expect("merge answers");
+        let merged = runner
+            .merge_answers(json!({"saved": 1}), &minimal_config())
+            .expect("merge answers");
 
         assert_eq!(merged["saved"], json!(1));
         assert_eq!(merged["from_file"], json!(2));
@@ -586,7 +649,9 @@ questions: {}
         args.answers = Some("[]".to_string());
 
         let runner = UpdateRunner::new(args);
-        let err = runner.merge_answers(json!({})).expect_err("expected error");
+        let err = runner
+            .merge_answers(json!({}), &minimal_config())
+            .expect_err("expected error");
 
         assert!(matches!(err, crate::error::Error::AnswersNotObject));
     }
@@ -597,7 +662,9 @@ questions: {}
         args.answers = Some(r#"{"k":"v"}"#.to_string());
         let runner = UpdateRunner::new(args);
 
-        let merged = runner.merge_answers(json!("old")).expect("merge should work");
+        let merged = runner
+            .merge_answers(json!("old"), &minimal_config())
+            .expect("merge should work");
         assert_eq!(merged, json!({"k": "v"}));
     }
 
@@ -631,7 +698,7 @@ questions: {}
 
         let valid = load_and_validate_config(&dir.path().to_path_buf())
             .expect("valid config should load");
-        assert_eq!(valid.template_suffix, ".baker.j2");
+        assert_eq!(valid.settings.template_suffix, ".baker.j2");
 
         fs::write(
             dir.path().join("baker.yaml"),
@@ -683,7 +750,7 @@ questions: {}
         );
 
         let mut engine = crate::template::get_template_engine();
-        add_templates_in_renderer(template_dir.path(), &config, &mut engine);
+        add_templates_in_renderer(template_dir.path(), &config, &mut engine).unwrap();
 
         let rendered = engine
             .render("{% include \"hello.j2\" %}", &json!({"name": "World"}), Some("test"))
@@ -0,0 +1,236 @@
+//! Loading of cookiecutter templates, whose `cookiecutter.json` is translated into a
+//! [`ConfigV2`] so that they can be generated without a `baker.yaml`
+
+use crate::config::loader::ConfigV2;
+use crate::constants::COOKIECUTTER_CONFIG_FILENAME;
+use crate::error::{Error, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Keys of `cookiecutter.json` that configure cookiecutter itself.
+const SETTINGS_KEYS: &[&str] = &[
+    "_copy_without_render",
+    "_extensions",
+    "_jinja2_env_vars",
+    "_new_lines",
+    "__prompts__",
+];
+
+/// Settings of a template loaded from `cookiecutter.json`
+#[derive(Debug)]
+pub struct Cookiecutter {
+    /// Directory holding the project files, e.g. `{{cookiecutter.project_slug}}`.
+    pub project_dir: String,
+    /// `_copy_without_render` patterns, relative to `project_dir`.
+    pub copy_without_render: GlobSet,
+}
+
+impl Cookiecutter {
+    /// Variable that holds the answers in cookiecutter templates.
+    pub const CONTEXT_ALIAS: &'static str = "cookiecutter";
+
+    /// Whether the file or directory at `relative_path` (below `project_dir`), or one
+    /// of its parent directories, is copied without rendering.
+    pub fn is_copy_only(&self, relative_path: &Path) -> bool {
+        relative_path
+            .ancestors()
+            .filter(|path| !path.as_os_str().is_empty())
+            .any(|path| self.copy_without_render.is_match(path))
+    }
+}
+
+/// Loads the `cookiecutter.json` of the template in `template_root`.
+///
+/// Every variable becomes a question: strings, numbers and booleans are asked with
+/// the value as default, lists become single-choice questions defaulting to their
+/// first item, and dicts become `json` questions. Private `_`-prefixed variables get
+/// their default without being asked. Prompts and choice labels come from
+/// `__prompts__`.
+///
+/// # Arguments
+/// * `template_root` - The directory containing `cookiecutter.json`
+///
+/// # Returns
+/// * `Result<ConfigV2>` - The configuration, with [`ConfigV2::cookiecutter`] set
+pub fn load(template_root: &Path) -> Result<ConfigV2> {
+    let content =
+        std::fs::read_to_string(template_root.join(COOKIECUTTER_CONFIG_FILENAME))?;
+    let variables: IndexMap<String, Value> = serde_json::from_str(&content)?;
+    let prompts = variables.get("__prompts__").and_then(Value::as_object);
+
+    for key in ["_extensions", "_jinja2_env_vars"] {
+        if variables.contains_key(key) {
+            log::warn!("{COOKIECUTTER_CONFIG_FILENAME}: '{key}' is not supported");
+        }
+    }
+
+    let mut config: ConfigV2 = serde_json::from_value(json!({}))?;
+    for (key, value) in
+        variables.iter().filter(|(key, _)| !SETTINGS_KEYS.contains(&key.as_str()))
+    {
+        let prompt = prompts.and_then(|prompts| prompts.get(key));
+        let question = serde_json::from_value(question(key, value, prompt))?;
+        config.questions.insert(key.clone(), question);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    let patterns = variables.get("_copy_without_render").and_then(Value::as_array);
+    for pattern in patterns.into_iter().flatten().filter_map(Value::as_str) {
+        builder.add(Glob::new(pattern)?);
+    }
+    config.cookiecutter = Some(Cookiecutter {
+        project_dir: find_project_dir(template_root)?,
+        copy_without_render: builder.build()?,
+    });
+    Ok(config)
+}
+
+/// Translates a single cookiecutter variable into a question document.
+fn question(key: &str, value: &Value, prompt: Option<&Value>) -> Value {
+    let help = match prompt {
+        Some(Value::String(help)) => help.as_str(),
+        Some(Value::Object(prompt)) => {
+            prompt.get("__prompt__").and_then(Value::as_str).unwrap_or(key)
+        }
+        _ => key,
+    };
+    let mut question = match value {
+        Value::Bool(_) => json!({ "type": "bool", "default": value }),
+        Value::Number(number) => json!({ "type": "str", "default": number.to_string() }),
+        Value::Array(items) => {
+            let labels = prompt.and_then(Value::as_object);
+            let choices: Vec<Value> = items
+                .iter()
+                .map(|item| {
+                    let label = item
+                        .as_str()
+                        .and_then(|item| labels?.get(item))
+                        .and_then(Value::as_str);
+                    match label {
+                        Some(label) => json!({ "value": item, "label": label }),
+                        None => item.clone(),
+                    }
+                })
+                .collect();
+            json!({
+                "type": "str",
+                "choices": choices,
+                "default": items.first().cloned().unwrap_or_default(),
+            })
+        }
+        Value::Object(_) => json!({ "type": "json", "default": value }),
+        _ => json!({ "type": "str", "default": value }),
+    };
+    question["help"] = help.into();
+    if key.starts_with('_') {
+        question["ask_if"] = "false".into();
+    }
+    question
+}
+
+/// Finds the top-level directory whose name renders into the project name, e.g.
+/// `{{cookiecutter.project_slug}}`.
+fn find_project_dir(template_root: &Path) -> Result<String> {
+    let mut candidates: Vec<String> = std::fs::read_dir(template_root)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| {
+            name.contains("cookiecutter") && name.contains("{{") && name.contains("}}")
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next().ok_or_else(|| {
+        Error::ConfigValidation(format!(
+            "{COOKIECUTTER_CONFIG_FILENAME} found, but no '{{{{cookiecutter.*}}}}' project directory next to it"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::IntoQuestionType;
+    use crate::config::QuestionType;
+
+    fn template(cookiecutter_json: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(COOKIECUTTER_CONFIG_FILENAME), cookiecutter_json)
+            .unwrap();
+        std::fs::create_dir(dir.path().join("{{cookiecutter.project_slug}}")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn translates_variables_into_questions() {
+        let dir = template(
+            r#"{
+  "project_name": "My Project",
+  "project_slug": "{{ cookiecutter.project_name.lower().replace(' ', '_') }}",
+  "license": ["MIT", "BSD-3"],
+  "use_docker": true,
+  "version": 1,
+  "settings": {"debug": false},
+  "_private": "hidden",
+  "_copy_without_render": ["*.html", "static"],
+  "__prompts__": {
+    "project_name": "What is your project called?",
+    "license": {"__prompt__": "Pick a license", "MIT": "MIT License"}
+  }
+}"#,
+        );
+
+        let config = load(dir.path()).unwrap();
+
+        let keys: Vec<&String> = config.questions.keys().collect();
+        assert_eq!(
+            keys,
+            vec![
+                "project_name",
+                "project_slug",
+                "license",
+                "use_docker",
+                "version",
+                "settings",
+                "_private"
+            ]
+        );
+        let name = &config.questions["project_name"];
+        assert_eq!(name.help, "What is your project called?");
+        assert_eq!(name.default, json!("My Project"));
+
+        let license = &config.questions["license"];
+        assert_eq!(license.help, "Pick a license");
+        assert_eq!(license.into_question_type(), QuestionType::SingleChoice);
+        assert_eq!(license.choices[0].label, "MIT License");
+        assert_eq!(license.choices[1].label, "BSD-3");
+        assert_eq!(license.default, json!("MIT"));
+
+        assert_eq!(
+            config.questions["use_docker"].into_question_type(),
+            QuestionType::Boolean
+        );
+        assert_eq!(config.questions["version"].default, json!("1"));
+        assert_eq!(config.questions["settings"].into_question_type(), QuestionType::Json);
+        assert_eq!(config.questions["_private"].ask_if, "false");
+        assert_eq!(config.questions["project_slug"].ask_if, "");
+
+        let cookiecutter = config.cookiecutter.unwrap();
+        assert_eq!(cookiecutter.project_dir, "{{cookiecutter.project_slug}}");
+        assert!(cookiecutter.is_copy_only(Path::new("templates/index.html")));
+        assert!(cookiecutter.is_copy_only(Path::new("static/app.js")));
+        assert!(!cookiecutter.is_copy_only(Path::new("README.md")));
+    }
+
+    #[test]
+    fn requires_a_project_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(COOKIECUTTER_CONFIG_FILENAME), "{}").unwrap();
+
+        let err = load(dir.path()).unwrap_err();
+        assert!(matches!(err, Error::ConfigValidation(_)));
+        assert!(err.to_string().contains("{{cookiecutter.*}}"));
+    }
+}
@@ -1,77 +1,332 @@
 //! Configuration loading and management
 
-use crate::config::question::Question;
+use crate::config::cookiecutter::{self, Cookiecutter};
+use crate::config::question::{Question, QuestionV1};
+use crate::config::schema::find_unknown_keys;
+use crate::config::types::CrossValidation;
 use crate::conflict::ConflictStyle;
 use crate::constants::{
-    CONFIG_FILENAMES, DEFAULT_LOOP_CONTENT_SEPARATOR, DEFAULT_LOOP_SEPARATOR,
-    DEFAULT_POST_HOOK, DEFAULT_PRE_HOOK, DEFAULT_TEMPLATE_SUFFIX,
+    ANSWER_VALUE_NAME, CONFIG_FILENAMES, COOKIECUTTER_CONFIG_FILENAME,
+    DEFAULT_LOOP_CONTENT_SEPARATOR, DEFAULT_LOOP_SEPARATOR, DEFAULT_POST_HOOK,
+    DEFAULT_PRE_HOOK, DEFAULT_TEMPLATE_SUFFIX,
 };
 use crate::error::{Error, Result};
 use crate::ext::PathExt;
+use crate::renderer::{Delimiters, Engine};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use indexmap::IndexMap;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::path::Path;
 
-/// Main configuration structure holding all questions
-#[derive(Debug, Deserialize)]
-pub struct ConfigV1 {
+/// Settings shared by every `schemaVersion`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TemplateSettings {
+    /// Template engine the files, paths and expressions are written for.
+    #[serde(default)]
+    pub engine: Engine,
+    /// Delimiters of blocks, variables and comments, for templates whose files contain
+    /// literal `{{ }}` or `{% %}`.
+    #[serde(default)]
+    pub delimiters: Delimiters,
+    /// Suffix marking files whose content is rendered (default: `.baker.j2`).
     #[serde(default = "get_default_template_suffix")]
     pub template_suffix: String,
+    /// Separator inserted between files generated from a loop in a file name.
     #[serde(default = "get_default_loop_separator")]
     pub loop_separator: String,
+    /// Marker separating per-item contents of a loop-generated file.
     #[serde(default = "get_default_loop_content_separator")]
     pub loop_content_separator: String,
+    /// Glob patterns of files that can be imported or included from templates.
     #[serde(default = "get_default_template_globs")]
     pub template_globs: Vec<String>,
+    /// Directory that `template_globs` are resolved against (default: template root).
     #[serde(default)]
     pub import_root: Option<String>,
+    /// Glob patterns of files whose content is rendered even without the
+    /// `template_suffix`. Matching files keep their name. `*` does not match `/`.
     #[serde(default)]
-    pub questions: IndexMap<String, Question>,
+    pub render_globs: Vec<String>,
+    /// Glob patterns of files and directories copied as-is, even when their name ends
+    /// with the `template_suffix`. Takes precedence over `render_globs`. `*` does not
+    /// match `/`.
+    #[serde(default)]
+    pub copy_without_render: Vec<String>,
+    /// Whether symlinks in the template are followed instead of recreated.
+    #[serde(default = "get_default_follow_symlinks")]
+    pub follow_symlinks: bool,
+    /// Name of the generated-metadata file written to the output directory.
+    /// Defaults to `.baker-generated.yaml`.
+    #[serde(default)]
+    pub generated_file_name: Option<String>,
+    /// Conflict-marker style used during `baker update`.
+    /// Defaults to `git` style.
+    #[serde(default)]
+    pub conflict_marker_style: Option<ConflictStyle>,
+    /// Parent template (local path or git URL) whose questions, ignore patterns
+    /// and files are layered under this template. Relative paths are resolved
+    /// against this template's directory.
+    #[serde(default)]
+    pub extends: Option<String>,
+    /// Rules over the full answer set, checked after all questions are answered.
+    #[serde(default)]
+    pub validations: Vec<CrossValidation>,
+    /// Whether templates fail on undefined variables instead of rendering them as
+    /// empty strings.
+    #[serde(default)]
+    pub strict_undefined: bool,
+    /// Template files whose macros are imported into every rendered file and path,
+    /// resolved like imports against `import_root`.
+    #[serde(default)]
+    pub prelude: Vec<String>,
+    /// Constants available to every template, path and expression.
+    #[serde(default)]
+    pub globals: IndexMap<String, serde_json::Value>,
+    /// Whether unknown keys are only logged as warnings instead of failing to load
+    /// the configuration, e.g. for keys read by other tools.
+    #[serde(default)]
+    pub allow_unknown_keys: bool,
+}
+
+/// Configuration in `schemaVersion: v1`, normalized into [`ConfigV2`] when loaded
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConfigV1 {
+    #[serde(flatten)]
+    pub settings: TemplateSettings,
+    /// Questions asked during generation, in order.
+    #[serde(default)]
+    pub questions: IndexMap<String, QuestionV1>,
+    /// File name of the post-generation hook inside `hooks/`.
     #[serde(default = "get_default_post_hook_filename")]
     pub post_hook_filename: String,
+    /// File name of the pre-generation hook inside `hooks/`.
     #[serde(default = "get_default_pre_hook_filename")]
     pub pre_hook_filename: String,
+    /// Command used to run the post hook (e.g. `["python3"]`).
     #[serde(default = "get_default_post_hook_runner")]
     pub post_hook_runner: Vec<String>,
+    /// Command used to run the pre hook (e.g. `["python3"]`).
     #[serde(default = "get_default_pre_hook_runner")]
     pub pre_hook_runner: Vec<String>,
+    /// Whether the post hook's stdout is printed to the terminal.
     #[serde(default = "get_default_post_hook_print_stdout")]
     pub post_hook_print_stdout: bool,
-    #[serde(default = "get_default_follow_symlinks")]
-    pub follow_symlinks: bool,
-    /// Name of the generated-metadata file written to the output directory.
-    /// Defaults to `.baker-generated.yaml`.
+}
+
+/// Main configuration structure holding all questions.
+///
+/// This is the `schemaVersion: v2` format and the model the rest of Baker works on;
+/// v1 configurations are converted into it when they are loaded.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConfigV2 {
+    #[serde(flatten)]
+    pub settings: TemplateSettings,
+    /// Questions asked during generation, in order.
     #[serde(default)]
-    pub generated_file_name: Option<String>,
-    /// Conflict-marker style used during `baker update`.
-    /// Defaults to `git` style.
+    pub questions: IndexMap<String, Question>,
+    /// Pre- and post-generation hooks.
     #[serde(default)]
-    pub conflict_marker_style: Option<ConflictStyle>,
+    pub hooks: Hooks,
+    /// Set when the template was loaded from `cookiecutter.json`.
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub cookiecutter: Option<Cookiecutter>,
+}
+
+/// Hooks run around generation, from the template's `hooks/` directory
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct Hooks {
+    /// Hook run before generation; its stdout may provide answers as JSON.
+    #[serde(default)]
+    pub pre: PreHook,
+    /// Hook run after the files are generated.
+    #[serde(default)]
+    pub post: PostHook,
+}
+
+/// The pre-generation hook
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PreHook {
+    /// File name of the hook inside `hooks/`.
+    #[serde(default = "get_default_pre_hook_filename")]
+    pub filename: String,
+    /// Command used to run the hook (e.g. `["python3"]`).
+    #[serde(default = "get_default_pre_hook_runner")]
+    pub runner: Vec<String>,
+}
+
+impl Default for PreHook {
+    fn default() -> Self {
+        Self {
+            filename: get_default_pre_hook_filename(),
+            runner: get_default_pre_hook_runner(),
+        }
+    }
+}
+
+/// The post-generation hook
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PostHook {
+    /// File name of the hook inside `hooks/`.
+    #[serde(default = "get_default_post_hook_filename")]
+    pub filename: String,
+    /// Command used to run the hook (e.g. `["python3"]`).
+    #[serde(default = "get_default_post_hook_runner")]
+    pub runner: Vec<String>,
+    /// Whether the hook's stdout is printed to the terminal.
+    #[serde(default = "get_default_post_hook_print_stdout")]
+    pub print_stdout: bool,
 }
 
-impl ConfigV1 {
+impl Default for PostHook {
+    fn default() -> Self {
+        Self {
+            filename: get_default_post_hook_filename(),
+            runner: get_default_post_hook_runner(),
+            print_stdout: get_default_post_hook_print_stdout(),
+        }
+    }
+}
+
+impl From<ConfigV1> for ConfigV2 {
+    fn from(v1: ConfigV1) -> Self {
+        Self {
+            settings: v1.settings,
+            questions: v1
+                .questions
+                .into_iter()
+                .map(|(key, question)| (key, question.into()))
+                .collect(),
+            hooks: Hooks {
+                pre: PreHook {
+                    filename: v1.pre_hook_filename,
+                    runner: v1.pre_hook_runner,
+                },
+                post: PostHook {
+                    filename: v1.post_hook_filename,
+                    runner: v1.post_hook_runner,
+                    print_stdout: v1.post_hook_print_stdout,
+                },
+            },
+            cookiecutter: None,
+        }
+    }
+}
+
+impl ConfigV2 {
     pub fn validate(&self) -> Result<(), Error> {
-        if self.template_suffix.is_empty() {
+        if self.settings.template_suffix.is_empty() {
             return Err(Error::ConfigValidation(
                 "template_suffix must not be empty".into(),
             ));
         }
-        if !self.template_suffix.starts_with('.') || self.template_suffix.len() < 2 {
+        if !self.settings.template_suffix.starts_with('.')
+            || self.settings.template_suffix.len() < 2
+        {
             return Err(Error::ConfigValidation("template_suffix must start with '.' and have at least 1 character after it".into()));
         }
+        if self.questions.contains_key(ANSWER_VALUE_NAME) {
+            return Err(Error::ConfigValidation(format!(
+                "'{ANSWER_VALUE_NAME}' is reserved for the answer in transforms and validation rules and cannot be a question name"
+            )));
+        }
+        build_globset(&self.settings.render_globs)?;
+        build_globset(&self.settings.copy_without_render)?;
         Ok(())
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(tag = "schemaVersion")]
 pub enum Config {
     #[serde(rename = "v1")]
     V1(ConfigV1),
+    #[serde(rename = "v2")]
+    V2(ConfigV2),
 }
 
 impl Config {
+    /// Converts the configuration into the model used by the rest of Baker.
+    pub fn normalize(self) -> ConfigV2 {
+        match self {
+            Config::V1(config) => config.into(),
+            Config::V2(config) => config,
+        }
+    }
+
+    /// Loads the configuration of the template in `template_root` and normalizes it.
+    /// Templates without a Baker configuration but with a `cookiecutter.json` are
+    /// loaded in cookiecutter compatibility mode.
+    pub fn load<P: AsRef<Path>>(template_root: P) -> Result<ConfigV2> {
+        let template_root = template_root.as_ref();
+        match Self::load_config(template_root) {
+            Err(Error::ConfigNotFound { .. })
+                if template_root.join(COOKIECUTTER_CONFIG_FILENAME).is_file() =>
+            {
+                log::debug!(
+                    "Loading {COOKIECUTTER_CONFIG_FILENAME} in compatibility mode"
+                );
+                cookiecutter::load(template_root)
+            }
+            config => Ok(config?.normalize()),
+        }
+    }
+
+    /// Loads the configuration of the template in `template_root`. Unknown keys are
+    /// an error, unless the configuration sets `allow_unknown_keys`.
     pub fn load_config<P: AsRef<Path>>(template_root: P) -> Result<Self> {
+        let (config_file_name, content) = Self::read_config_file(template_root)?;
+
+        let raw = Self::parse_raw(config_file_name, &content)?;
+        let unknown_keys = find_unknown_keys(&raw);
+        if raw.get("allow_unknown_keys") == Some(&serde_json::Value::Bool(true)) {
+            for unknown in &unknown_keys {
+                log::warn!("{config_file_name}: {unknown}");
+            }
+        } else if !unknown_keys.is_empty() {
+            let unknown_keys: Vec<String> =
+                unknown_keys.iter().map(ToString::to_string).collect();
+            return Err(Error::ConfigValidation(format!(
+                "{config_file_name}: {}",
+                unknown_keys.join(", ")
+            )));
+        }
+
+        Self::parse_config(config_file_name, &content)
+    }
+
+    /// Loads the configuration like [`Config::load_config`], without checking for
+    /// unknown keys. Used by `baker lint`, which reports them itself.
+    pub fn load_config_unchecked<P: AsRef<Path>>(template_root: P) -> Result<Self> {
+        let (config_file_name, content) = Self::read_config_file(template_root)?;
+        Self::parse_config(config_file_name, &content)
+    }
+
+    fn parse_config(config_file_name: &str, content: &str) -> Result<Self> {
+        let config: Config = match config_file_name {
+            "baker.json" => serde_json::from_str(content)?,
+            "baker.yaml" | "baker.yml" => serde_yaml::from_str(content)?,
+            "baker.toml" => toml::from_str(content)?,
+            _ => unreachable!(),
+        };
+
+        Ok(config)
+    }
+
+    /// Loads the configuration file as an untyped document, returning the name of the
+    /// file it was read from. Used by `baker lint` to inspect keys serde would ignore.
+    pub fn load_raw<P: AsRef<Path>>(
+        template_root: P,
+    ) -> Result<(&'static str, serde_json::Value)> {
+        let (config_file_name, content) = Self::read_config_file(template_root)?;
+        Ok((config_file_name, Self::parse_raw(config_file_name, &content)?))
+    }
+
+    /// Reads the template's configuration file, returning its name and content.
+    pub(crate) fn read_config_file<P: AsRef<Path>>(
+        template_root: P,
+    ) -> Result<(&'static str, String)> {
         let template_root = template_root.as_ref().to_path_buf();
         let template_dir = template_root.to_str_checked()?.to_string();
 
@@ -80,13 +335,7 @@ impl Config {
 
             if config_file_path.exists() {
                 let content = std::fs::read_to_string(config_file_path)?;
-                let config: Config = match *config_file_name {
-                    "baker.json" => serde_json::from_str(&content)?,
-                    "baker.yaml" | "baker.yml" => serde_yaml::from_str(&content)?,
-                    _ => unreachable!(),
-                };
-
-                return Ok(config);
+                return Ok((config_file_name, content));
             }
         }
 
@@ -95,6 +344,27 @@ impl Config {
             config_files: CONFIG_FILENAMES.join(", "),
         })
     }
+
+    fn parse_raw(config_file_name: &str, content: &str) -> Result<serde_json::Value> {
+        Ok(match config_file_name {
+            "baker.json" => serde_json::from_str(content)?,
+            "baker.yaml" | "baker.yml" => serde_yaml::from_str(content)?,
+            "baker.toml" => toml::from_str(content)?,
+            _ => unreachable!(),
+        })
+    }
+}
+
+/// Compiles glob patterns that are matched against paths relative to the template
+/// root, such as `render_globs` and `copy_without_render`. `*` does not match `/`,
+/// so `*.toml` only matches files in the template root and `**/*.toml` matches them
+/// in every directory.
+pub fn build_globset(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+    }
+    Ok(builder.build()?)
 }
 
 fn get_default_template_suffix() -> String {
@@ -151,10 +421,10 @@ questions: {}
 "#;
 
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
+        let cfg = config.normalize();
 
-        assert!(cfg.pre_hook_runner.is_empty());
-        assert!(cfg.post_hook_runner.is_empty());
+        assert!(cfg.hooks.pre.runner.is_empty());
+        assert!(cfg.hooks.post.runner.is_empty());
     }
 
     #[test]
@@ -169,13 +439,13 @@ questions: {}
 "#;
 
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
+        let cfg = config.normalize();
 
         assert_eq!(
-            cfg.pre_hook_runner,
+            cfg.hooks.pre.runner,
             vec!["powershell".to_string(), "-File".to_string()]
         );
-        assert_eq!(cfg.post_hook_runner, vec!["python3".to_string(), "-u".to_string()]);
+        assert_eq!(cfg.hooks.post.runner, vec!["python3".to_string(), "-u".to_string()]);
     }
 
     #[test]
@@ -183,8 +453,8 @@ questions: {}
         let raw = r#"schemaVersion: v1
 questions: {}"#;
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
-        assert!(!cfg.follow_symlinks);
+        let cfg = config.normalize();
+        assert!(!cfg.settings.follow_symlinks);
     }
 
     #[test]
@@ -192,8 +462,8 @@ questions: {}"#;
         let raw = r#"schemaVersion: v1
 questions: {}"#;
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
-        assert!(!cfg.post_hook_print_stdout);
+        let cfg = config.normalize();
+        assert!(!cfg.hooks.post.print_stdout);
     }
 
     #[test]
@@ -202,8 +472,17 @@ questions: {}"#;
 post_hook_print_stdout: true
 questions: {}"#;
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
-        assert!(cfg.post_hook_print_stdout);
+        let cfg = config.normalize();
+        assert!(cfg.hooks.post.print_stdout);
+    }
+
+    #[test]
+    fn strict_undefined_defaults_false_and_parses() {
+        let cfg = |raw: &str| serde_yaml::from_str::<Config>(raw).unwrap().normalize();
+        assert!(!cfg("schemaVersion: v2\nquestions: {}").settings.strict_undefined);
+        assert!(
+            cfg("schemaVersion: v2\nstrict_undefined: true").settings.strict_undefined
+        );
     }
 
     #[test]
@@ -212,8 +491,90 @@ questions: {}"#;
 follow_symlinks: true
 questions: {}"#;
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
-        assert!(cfg.follow_symlinks);
+        let cfg = config.normalize();
+        assert!(cfg.settings.follow_symlinks);
+    }
+
+    #[test]
+    fn engine_defaults_to_minijinja_and_parses() {
+        let cfg = |raw: &str| serde_yaml::from_str::<Config>(raw).unwrap().normalize();
+        assert_eq!(cfg("schemaVersion: v2").settings.engine, Engine::Minijinja);
+        assert_eq!(
+            cfg("schemaVersion: v1\nengine: handlebars").settings.engine,
+            Engine::Handlebars
+        );
+        assert_eq!(cfg("schemaVersion: v2\nengine: tera").settings.engine, Engine::Tera);
+        assert!(serde_yaml::from_str::<Config>("schemaVersion: v2\nengine: erb").is_err());
+    }
+
+    #[test]
+    fn delimiters_default_to_jinja_and_can_be_partly_overridden() {
+        let cfg = |raw: &str| serde_yaml::from_str::<Config>(raw).unwrap().normalize();
+        assert_eq!(cfg("schemaVersion: v2").settings.delimiters, Delimiters::default());
+
+        let config = cfg("schemaVersion: v1\ndelimiters:\n  variable_start: '[['\n  variable_end: ']]'");
+        assert_eq!(config.settings.delimiters.variable_start, "[[");
+        assert_eq!(config.settings.delimiters.variable_end, "]]");
+        assert_eq!(config.settings.delimiters.block_start, "{%");
+    }
+
+    #[test]
+    fn prelude_and_globals_parse() {
+        let cfg = |raw: &str| serde_yaml::from_str::<Config>(raw).unwrap().normalize();
+        let config = cfg("schemaVersion: v2\nquestions: {}");
+        assert!(config.settings.prelude.is_empty());
+        assert!(config.settings.globals.is_empty());
+
+        let config = cfg(r#"
+schemaVersion: v1
+prelude: ["macros.j2"]
+globals:
+  company: Acme
+  ports: [80, 443]
+"#);
+        assert_eq!(config.settings.prelude, vec!["macros.j2".to_string()]);
+        assert_eq!(config.settings.globals["company"], serde_json::json!("Acme"));
+        assert_eq!(config.settings.globals["ports"], serde_json::json!([80, 443]));
+    }
+
+    #[test]
+    fn render_globs_and_copy_without_render_parse_and_validate() {
+        let cfg = |raw: &str| serde_yaml::from_str::<Config>(raw).unwrap().normalize();
+        let config = cfg("schemaVersion: v2\nquestions: {}");
+        assert!(config.settings.render_globs.is_empty());
+        assert!(config.settings.copy_without_render.is_empty());
+
+        let config = cfg(r#"
+schemaVersion: v1
+render_globs: ["**/*.toml"]
+copy_without_render: ["static", "*.min.js"]
+"#);
+        assert_eq!(config.settings.render_globs, vec!["**/*.toml".to_string()]);
+        assert_eq!(config.settings.copy_without_render, vec!["static", "*.min.js"]);
+        assert!(config.validate().is_ok());
+
+        let globs = build_globset(&config.settings.render_globs).unwrap();
+        assert!(globs.is_match("Cargo.toml"));
+        assert!(globs.is_match("crates/core/Cargo.toml"));
+        let globs = build_globset(&["*.toml".to_string()]).unwrap();
+        assert!(globs.is_match("Cargo.toml"));
+        assert!(!globs.is_match("crates/core/Cargo.toml"));
+
+        let config = cfg("schemaVersion: v2\nrender_globs: [\"src/[\"]");
+        assert!(matches!(config.validate(), Err(Error::GlobSetParseError(_))));
+    }
+
+    #[test]
+    fn a_question_named_value_is_rejected() {
+        let cfg = |raw: &str| serde_yaml::from_str::<Config>(raw).unwrap().normalize();
+        let config = cfg("schemaVersion: v2\nquestions:\n  values:\n    type: str");
+        assert!(config.validate().is_ok());
+
+        let config = cfg("schemaVersion: v2\nquestions:\n  value:\n    type: str");
+        assert!(matches!(
+            config.validate(),
+            Err(Error::ConfigValidation(msg)) if msg.contains("'value' is reserved")
+        ));
     }
 
     #[test]
@@ -221,8 +582,8 @@ questions: {}"#;
         let raw = r#"schemaVersion: v1
 questions: {}"#;
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
-        assert!(cfg.import_root.is_none());
+        let cfg = config.normalize();
+        assert!(cfg.settings.import_root.is_none());
     }
 
     #[test]
@@ -231,8 +592,8 @@ questions: {}"#;
 import_root: "templates/shared"
 questions: {}"#;
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
-        assert_eq!(cfg.import_root, Some("templates/shared".to_string()));
+        let cfg = config.normalize();
+        assert_eq!(cfg.settings.import_root, Some("templates/shared".to_string()));
     }
 
     #[test]
@@ -241,7 +602,128 @@ questions: {}"#;
 import_root: "/usr/local/templates"
 questions: {}"#;
         let config: Config = serde_yaml::from_str(raw).expect("valid config");
-        let Config::V1(cfg) = config;
-        assert_eq!(cfg.import_root, Some("/usr/local/templates".to_string()));
+        let cfg = config.normalize();
+        assert_eq!(cfg.settings.import_root, Some("/usr/local/templates".to_string()));
+    }
+
+    #[test]
+    fn unknown_keys_fail_to_load_unless_allowed() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = "schemaVersion: v1\nquestions:\n  password:\n    type: str\n    secret:\n      confrim: true\n";
+        std::fs::write(dir.path().join("baker.yaml"), config).unwrap();
+
+        let err = Config::load(dir.path()).unwrap_err();
+        assert!(matches!(err, Error::ConfigValidation(_)));
+        assert!(err.to_string().contains(
+            "unknown key 'questions.password.secret.confrim' (did you mean 'confirm'?)"
+        ));
+        assert!(Config::load_config_unchecked(dir.path()).is_ok());
+
+        let config = format!("allow_unknown_keys: true\n{config}");
+        std::fs::write(dir.path().join("baker.yaml"), config).unwrap();
+        assert!(Config::load(dir.path()).is_ok());
+    }
+
+    #[test]
+    fn loads_toml_configuration() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("baker.toml"),
+            r#"schemaVersion = "v2"
+template_suffix = ".tpl"
+
+[hooks.post]
+runner = ["python3"]
+
+[questions.name]
+type = "str"
+help = "Crate name"
+default = "demo"
+
+[questions.edition]
+type = "str"
+choices = ["2021", "2024"]
+default = "2024"
+"#,
+        )
+        .unwrap();
+
+        let cfg = Config::load(dir.path()).expect("valid config");
+
+        assert_eq!(cfg.settings.template_suffix, ".tpl");
+        assert_eq!(cfg.hooks.post.runner, vec!["python3".to_string()]);
+        let keys: Vec<&String> = cfg.questions.keys().collect();
+        assert_eq!(keys, vec!["name", "edition"]);
+        assert_eq!(cfg.questions["name"].default, serde_json::json!("demo"));
+        assert_eq!(cfg.questions["edition"].choices.len(), 2);
+    }
+
+    #[test]
+    fn v1_questions_are_normalized() {
+        let raw = r#"schemaVersion: v1
+questions:
+  db:
+    type: str
+    choices: [postgres, sqlite]
+  password:
+    type: str
+    secret:
+      confirm: true
+      mistmatch_err: "No match"
+"#;
+        let cfg = serde_yaml::from_str::<Config>(raw).expect("valid config").normalize();
+
+        let choices: Vec<&str> =
+            cfg.questions["db"].choices.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(choices, vec!["postgres", "sqlite"]);
+        let secret = cfg.questions["password"].secret.as_ref().unwrap();
+        assert!(secret.confirm);
+        assert_eq!(secret.mismatch_error, "No match");
+    }
+
+    #[test]
+    fn v2_parses_hooks_and_labeled_choices() {
+        let raw = r#"schemaVersion: v2
+hooks:
+  pre:
+    runner: ["python3"]
+  post:
+    filename: post.sh
+    print_stdout: true
+questions:
+  port:
+    type: str
+    default: 8080
+    choices:
+      - 8080
+      - value: 443
+        label: HTTPS
+  password:
+    type: str
+    secret:
+      mismatch_error: "No match"
+"#;
+        let cfg = serde_yaml::from_str::<Config>(raw).expect("valid config").normalize();
+
+        assert_eq!(cfg.hooks.pre.filename, "pre");
+        assert_eq!(cfg.hooks.pre.runner, vec!["python3".to_string()]);
+        assert_eq!(cfg.hooks.post.filename, "post.sh");
+        assert!(cfg.hooks.post.print_stdout);
+        let port = &cfg.questions["port"];
+        assert_eq!(port.choices[0].value, serde_json::json!(8080));
+        assert_eq!(port.choices[1].label, "HTTPS");
+        assert_eq!(
+            cfg.questions["password"].secret.as_ref().unwrap().mismatch_error,
+            "No match"
+        );
+    }
+
+    #[test]
+    fn v2_ignores_v1_hook_fields() {
+        let raw = r#"schemaVersion: v2
+post_hook_filename: setup
+questions: {}"#;
+        let cfg = serde_yaml::from_str::<Config>(raw).expect("valid config").normalize();
+        assert_eq!(cfg.hooks.post.filename, "post");
     }
 }
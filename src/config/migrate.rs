@@ -0,0 +1,166 @@
+//! Rewriting of `schemaVersion: v1` configuration documents into `schemaVersion: v2`
+
+use crate::config::loader::Config;
+use crate::error::{Error, Result};
+use serde_yaml::{Mapping, Value};
+
+/// v1 hook keys and the `hooks.<hook>.<key>` they move to in v2.
+const HOOK_KEYS: &[(&str, &str, &str)] = &[
+    ("pre_hook_filename", "pre", "filename"),
+    ("pre_hook_runner", "pre", "runner"),
+    ("post_hook_filename", "post", "filename"),
+    ("post_hook_runner", "post", "runner"),
+    ("post_hook_print_stdout", "post", "print_stdout"),
+];
+
+/// Rewrites a v1 configuration document into v2.
+///
+/// Only keys present in the document are carried over, in their original order:
+/// the separate `pre_hook_*`/`post_hook_*` keys are grouped under `hooks`, and
+/// `secret.mistmatch_err` becomes `secret.mismatch_error`. String choices are
+/// valid in v2 as they are.
+///
+/// # Arguments
+/// * `document` - The parsed v1 configuration file
+///
+/// # Returns
+/// * `Result<Value>` - The v2 document, checked to load as a configuration
+pub fn migrate_to_v2(document: &Value) -> Result<Value> {
+    let entries = document.as_mapping().ok_or_else(|| {
+        Error::ConfigValidation("the configuration must be a mapping".into())
+    })?;
+    match entries.get("schemaVersion").and_then(Value::as_str) {
+        Some("v1") => {}
+        found => {
+            return Err(Error::UnsupportedConfigVersion {
+                found: found.unwrap_or_default().to_string(),
+            })
+        }
+    }
+
+    let mut migrated = Mapping::new();
+    let (mut pre, mut post) = (Mapping::new(), Mapping::new());
+    for (key, value) in entries {
+        let name = key.as_str().unwrap_or_default();
+        if let Some((_, hook, field)) = HOOK_KEYS.iter().find(|(k, ..)| *k == name) {
+            // Keep `hooks` where the first hook key was; it is filled in below.
+            if !migrated.contains_key("hooks") {
+                migrated.insert("hooks".into(), Value::Null);
+            }
+            let hook = if *hook == "pre" { &mut pre } else { &mut post };
+            hook.insert((*field).into(), value.clone());
+            continue;
+        }
+        let value = match name {
+            "schemaVersion" => "v2".into(),
+            "questions" => migrate_questions(value),
+            _ => value.clone(),
+        };
+        migrated.insert(key.clone(), value);
+    }
+    if let Some(hooks) = migrated.get_mut("hooks") {
+        let mut grouped = Mapping::new();
+        for (name, hook) in [("pre", pre), ("post", post)] {
+            if !hook.is_empty() {
+                grouped.insert(name.into(), Value::Mapping(hook));
+            }
+        }
+        *hooks = Value::Mapping(grouped);
+    }
+
+    let migrated = Value::Mapping(migrated);
+    serde_yaml::from_value::<Config>(migrated.clone())?;
+    Ok(migrated)
+}
+
+fn migrate_questions(questions: &Value) -> Value {
+    let Some(questions) = questions.as_mapping() else {
+        return questions.clone();
+    };
+    let questions = questions
+        .iter()
+        .map(|(name, question)| {
+            let mut question = question.clone();
+            if let Some(Value::Mapping(secret)) =
+                question.as_mapping_mut().and_then(|q| q.get_mut("secret"))
+            {
+                *secret = secret
+                    .iter()
+                    .map(|(key, value)| match key.as_str() {
+                        Some("mistmatch_err") => ("mismatch_error".into(), value.clone()),
+                        _ => (key.clone(), value.clone()),
+                    })
+                    .collect();
+            }
+            (name.clone(), question)
+        })
+        .collect();
+    Value::Mapping(questions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_hooks_and_renames_secret_options() {
+        let v1: Value = serde_yaml::from_str(
+            r#"
+schemaVersion: v1
+template_suffix: .j2
+post_hook_filename: setup.sh
+questions:
+  password:
+    type: str
+    secret:
+      confirm: true
+      mistmatch_err: No match
+  db:
+    type: str
+    choices: [postgres, sqlite]
+pre_hook_runner: [python3]
+post_hook_print_stdout: true
+"#,
+        )
+        .unwrap();
+
+        let v2 = migrate_to_v2(&v1).unwrap();
+
+        let expected: Value = serde_yaml::from_str(
+            r#"
+schemaVersion: v2
+template_suffix: .j2
+hooks:
+  pre:
+    runner: [python3]
+  post:
+    filename: setup.sh
+    print_stdout: true
+questions:
+  password:
+    type: str
+    secret:
+      confirm: true
+      mismatch_error: No match
+  db:
+    type: str
+    choices: [postgres, sqlite]
+"#,
+        )
+        .unwrap();
+        assert_eq!(v2, expected);
+
+        let keys: Vec<&str> =
+            v2.as_mapping().unwrap().keys().filter_map(Value::as_str).collect();
+        assert_eq!(keys, vec!["schemaVersion", "template_suffix", "hooks", "questions"]);
+    }
+
+    #[test]
+    fn rejects_documents_that_are_not_v1() {
+        let v2: Value = serde_yaml::from_str("schemaVersion: v2\nquestions: {}").unwrap();
+        assert!(matches!(
+            migrate_to_v2(&v2),
+            Err(Error::UnsupportedConfigVersion { found }) if found == "v2"
+        ));
+    }
+}
@@ -4,15 +4,26 @@
 //! - `types`: Basic types and enums used throughout the config system
 //! - `question`: Question definition and rendering logic
 //! - `loader`: Configuration file loading and parsing
+//! - `cookiecutter`: Translation of `cookiecutter.json` templates
+//! - `migrate`: Rewriting v1 configuration files into v2
+//! - `schema`: JSON Schema generation and unknown-key detection
 
+pub mod cookiecutter;
 pub mod loader;
+pub mod migrate;
 pub mod question;
+pub mod schema;
 pub mod types;
 
 #[cfg(test)]
 mod tests;
 
 // Re-export commonly used types for convenience
-pub use loader::{Config, ConfigV1};
-pub use question::{IntoQuestionType, Question, QuestionRendered};
-pub use types::{QuestionType, Secret, Type, Validation};
+pub use loader::{
+    Config, ConfigV1, ConfigV2, Hooks, PostHook, PreHook, TemplateSettings,
+};
+pub use question::{IntoQuestionType, Question, QuestionRendered, QuestionV1};
+pub use types::{
+    Choice, CrossValidation, PathOptions, QuestionType, Secret, SecretV1, Type,
+    Validation,
+};
@@ -1,13 +1,17 @@
 //! Question configuration and rendering logic
 
 use crate::config::types::{
-    get_default_validation, QuestionType, Secret, Type, Validation,
+    one_or_many, Choice, OneOrMany, PathOptions, QuestionType, Secret, SecretV1, Type,
+    Validation,
 };
+use crate::constants::DEFAULT_DATE_FORMAT;
 use crate::renderer::TemplateRenderer;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
 use serde::Deserialize;
+use std::borrow::Cow;
 
 /// Represents a single question in the configuration
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Question {
     /// Help text/prompt to display to the user
     #[serde(default)]
@@ -18,15 +22,16 @@ pub struct Question {
     /// Optional default value for the question
     #[serde(default)]
     pub default: serde_json::Value,
-    /// Available choices for string questions
+    /// Available choices: plain values or `{ value, label }` entries
     #[serde(default)]
-    pub choices: Vec<String>,
+    pub choices: Vec<Choice>,
     /// Available option for string questions
     #[serde(default)]
     pub multiselect: bool,
     /// Whether the string is a secret
     #[serde(default)]
     pub secret: Option<Secret>,
+    /// Expression deciding whether the question is asked
     #[serde(default)]
     pub ask_if: String,
     /// JSON Schema for validation (for Json and Yaml types)
@@ -35,8 +40,100 @@ pub struct Question {
     /// Path to a file containing JSON Schema for validation (for Json and Yaml types)
     #[serde(default)]
     pub schema_file: Option<String>,
-    #[serde(default = "get_default_validation")]
-    pub validation: Validation,
+    /// Fail in non-interactive mode when the question has no answer and no default
+    #[serde(default)]
+    pub required: bool,
+    /// Whether an empty string is accepted for text questions
+    #[serde(default = "default_allow_empty")]
+    pub allow_empty: bool,
+    /// Environment variable to read the answer from
+    #[serde(default)]
+    pub env: Option<String>,
+    /// Shell command whose standard output is the answer, e.g. `pass show api-key`.
+    /// The answer is never written to the generated metadata file.
+    #[serde(default)]
+    pub secret_command: Option<String>,
+    /// Existence and extension checks for path questions
+    #[serde(default)]
+    pub path: Option<PathOptions>,
+    /// `strftime`-style format of date answers, `%Y-%m-%d` by default
+    #[serde(default)]
+    pub format: Option<String>,
+    /// MiniJinja filter chain applied to the answer before it is validated and stored,
+    /// e.g. `trim | lower`
+    #[serde(default)]
+    pub transform: Option<String>,
+    /// Rules the answer must satisfy: a single rule or a list of rules
+    #[serde(default, deserialize_with = "one_or_many")]
+    #[schemars(with = "OneOrMany<Validation>")]
+    pub validation: Vec<Validation>,
+}
+
+fn default_allow_empty() -> bool {
+    true
+}
+
+/// A question in `schemaVersion: v1`, which only allows string choices and spells the
+/// secret mismatch error `mistmatch_err`.
+#[derive(Debug, Deserialize)]
+pub struct QuestionV1 {
+    /// Available choices for string questions
+    #[serde(default)]
+    pub choices: Vec<String>,
+    /// Whether the string is a secret
+    #[serde(default)]
+    pub secret: Option<SecretV1>,
+    #[serde(flatten)]
+    pub question: Question,
+}
+
+impl JsonSchema for QuestionV1 {
+    fn schema_name() -> Cow<'static, str> {
+        "QuestionV1".into()
+    }
+
+    /// The schema of [`Question`] with the v1 `choices` and `secret` properties.
+    /// Deriving it would let the flattened question's properties win.
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let mut schema = Question::json_schema(generator);
+        let secret = generator.subschema_for::<SecretV1>();
+        if let Some(properties) =
+            schema.get_mut("properties").and_then(|p| p.as_object_mut())
+        {
+            properties.insert(
+                "choices".to_string(),
+                serde_json::json!({
+                    "description": "Available choices for string questions",
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "default": [],
+                }),
+            );
+            properties.insert(
+                "secret".to_string(),
+                serde_json::json!({
+                    "description": "Whether the string is a secret",
+                    "anyOf": [secret, { "type": "null" }],
+                    "default": null,
+                }),
+            );
+        }
+        schema.insert(
+            "description".to_string(),
+            "A question in `schemaVersion: v1`".into(),
+        );
+        schema
+    }
+}
+
+impl From<QuestionV1> for Question {
+    fn from(v1: QuestionV1) -> Self {
+        Self {
+            choices: v1.choices.into_iter().map(Choice::from).collect(),
+            secret: v1.secret.map(Secret::from),
+            ..v1.question
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -66,11 +163,19 @@ impl IntoQuestionType for Question {
             (Type::Bool, _) => QuestionType::Boolean,
             (Type::Json, _) => QuestionType::Json,
             (Type::Yaml, _) => QuestionType::Yaml,
+            (Type::Path, _) => QuestionType::Path,
+            (Type::Url, _) => QuestionType::Url,
+            (Type::Date, _) => QuestionType::Date,
         }
     }
 }
 
 impl Question {
+    /// Format date answers are parsed and defaults are rendered with.
+    pub fn date_format(&self) -> &str {
+        self.format.as_deref().unwrap_or(DEFAULT_DATE_FORMAT)
+    }
+
     fn question_type(&self) -> QuestionType {
         self.into_question_type()
     }
@@ -100,9 +205,12 @@ impl Question {
             QuestionType::Boolean => {
                 serde_json::Value::Bool(default.as_bool().unwrap_or(false))
             }
-            QuestionType::SingleChoice | QuestionType::Text => {
-                self.render_textual_default(default, answers, engine)
-            }
+            QuestionType::SingleChoice if !default.is_string() => default,
+            QuestionType::SingleChoice
+            | QuestionType::Text
+            | QuestionType::Path
+            | QuestionType::Url => self.render_textual_default(default, answers, engine),
+            QuestionType::Date => self.render_date_default(default, answers, engine),
             QuestionType::Json | QuestionType::Yaml => {
                 self.render_structured_default(default, answers, engine, question_type)
             }
@@ -122,6 +230,24 @@ impl Question {
         serde_json::Value::String(rendered)
     }
 
+    /// Renders a date default; the special value `today` becomes the current date
+    /// in the question's format.
+    fn render_date_default(
+        &self,
+        default: serde_json::Value,
+        answers: &serde_json::Value,
+        engine: &dyn TemplateRenderer,
+    ) -> serde_json::Value {
+        match self.render_textual_default(default, answers, engine) {
+            serde_json::Value::String(s) if s.trim().eq_ignore_ascii_case("today") => {
+                serde_json::Value::String(
+                    chrono::Local::now().format(self.date_format()).to_string(),
+                )
+            }
+            rendered => rendered,
+        }
+    }
+
     fn render_structured_default(
         &self,
         default: serde_json::Value,
@@ -185,6 +311,7 @@ impl Question {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::get_default_validation;
     use crate::renderer::MiniJinjaRenderer;
     use serde_json::json;
 
@@ -203,7 +330,14 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         }
     }
 
@@ -265,6 +399,30 @@ mod tests {
         assert_eq!(rendered.default, json!({ "enabled": true }));
     }
 
+    #[test]
+    fn date_default_today_uses_question_format() {
+        let mut question = base_question(Type::Date, json!("today"));
+        question.format = Some("%d.%m.%Y".to_string());
+        let renderer = build_renderer();
+
+        let rendered = question.render("released", &json!({}), &renderer);
+
+        let expected = chrono::Local::now().format("%d.%m.%Y").to_string();
+        assert_eq!(rendered.default, json!(expected));
+        assert_eq!(rendered.r#type, QuestionType::Date);
+    }
+
+    #[test]
+    fn path_and_url_defaults_are_rendered_as_text() {
+        let question = base_question(Type::Url, json!("https://{{ host }}/"));
+        let renderer = build_renderer();
+
+        let rendered =
+            question.render("homepage", &json!({ "host": "x.dev" }), &renderer);
+
+        assert_eq!(rendered.default, json!("https://x.dev/"));
+    }
+
     #[test]
     fn boolean_defaults_are_rendered_from_value() {
         let question = base_question(Type::Bool, json!(true));
@@ -0,0 +1,265 @@
+//! JSON Schema for `baker.yaml` and detection of unknown configuration keys
+
+use crate::config::loader::Config;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Returns the JSON Schema describing Baker's configuration file.
+///
+/// The schema is generated from [`Config`] and its nested types, so it always matches
+/// what Baker actually accepts. Unknown top-level keys are rejected by editors through
+/// `unevaluatedProperties`.
+pub fn config_schema() -> Value {
+    let mut schema = schemars::schema_for!(Config).to_value();
+    if let Some(variants) = schema.get_mut("oneOf").and_then(Value::as_array_mut) {
+        for variant in variants.iter_mut().filter_map(Value::as_object_mut) {
+            variant.insert("unevaluatedProperties".to_string(), Value::Bool(false));
+        }
+    }
+    schema
+}
+
+/// A configuration key that Baker does not recognise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownKey {
+    /// Dotted path to the key, e.g. `questions.name.ask-if`.
+    pub path: String,
+    /// The closest known key at the same level, if any is similar enough.
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown key '{}'", self.path)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean '{suggestion}'?)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds every key in a raw configuration document that is not part of the schema.
+///
+/// # Arguments
+/// * `raw` - The configuration file parsed into a generic JSON value
+///
+/// # Returns
+/// * `Vec<UnknownKey>` - Every unknown key, with a suggestion when one is close
+pub fn find_unknown_keys(raw: &Value) -> Vec<UnknownKey> {
+    let schema = schemars::schema_for!(Config).to_value();
+    let mut unknown = Vec::new();
+    let node = version_schema(raw, &schema).unwrap_or(&schema);
+    walk(raw, node, &schema, "", &mut unknown);
+    unknown
+}
+
+/// Picks the branch of the schema matching the document's `schemaVersion`, so keys
+/// of the other versions are reported as unknown.
+fn version_schema<'a>(raw: &Value, schema: &'a Value) -> Option<&'a Value> {
+    let version = raw.get("schemaVersion")?;
+    schema
+        .get("oneOf")?
+        .as_array()?
+        .iter()
+        .find(|branch| branch.pointer("/properties/schemaVersion/const") == Some(version))
+}
+
+fn walk(
+    value: &Value,
+    node: &Value,
+    root: &Value,
+    path: &str,
+    out: &mut Vec<UnknownKey>,
+) {
+    match value {
+        Value::Object(entries) => {
+            let mut properties = Map::new();
+            let mut additional = None;
+            collect_object_schema(node, root, &mut properties, &mut additional);
+            if properties.is_empty() && additional.is_none() {
+                return;
+            }
+            for (key, child) in entries {
+                let child_path =
+                    if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+                if let Some(child_node) = properties.get(key).or(additional) {
+                    walk(child, child_node, root, &child_path, out);
+                } else {
+                    out.push(UnknownKey {
+                        path: child_path,
+                        suggestion: suggest(key, properties.keys()),
+                    });
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(items_node) = find_items_schema(node, root) {
+                for (index, item) in items.iter().enumerate() {
+                    walk(item, items_node, root, &format!("{path}[{index}]"), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Gathers the known properties (and the schema for arbitrary map values) of an
+/// object schema, following `$ref` and the branches of `allOf`/`anyOf`/`oneOf`.
+fn collect_object_schema<'a>(
+    node: &'a Value,
+    root: &'a Value,
+    properties: &mut Map<String, Value>,
+    additional: &mut Option<&'a Value>,
+) {
+    if let Some(reference) = node.get("$ref") {
+        collect_object_schema(resolve_ref(reference, root), root, properties, additional);
+    }
+    if let Some(props) = node.get("properties").and_then(Value::as_object) {
+        properties.extend(props.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    if let Some(extra) = node.get("additionalProperties").filter(|v| v.is_object()) {
+        *additional = Some(extra);
+    }
+    for combinator in ["allOf", "anyOf", "oneOf"] {
+        for branch in node.get(combinator).and_then(Value::as_array).into_iter().flatten()
+        {
+            collect_object_schema(branch, root, properties, additional);
+        }
+    }
+}
+
+/// Finds the schema of array items, following `$ref` and `allOf`/`anyOf`/`oneOf`.
+fn find_items_schema<'a>(node: &'a Value, root: &'a Value) -> Option<&'a Value> {
+    let node = resolve(node, root);
+    node.get("items").filter(|items| items.is_object()).or_else(|| {
+        ["allOf", "anyOf", "oneOf"]
+            .iter()
+            .filter_map(|combinator| node.get(*combinator).and_then(Value::as_array))
+            .flatten()
+            .find_map(|branch| find_items_schema(branch, root))
+    })
+}
+
+fn resolve<'a>(node: &'a Value, root: &'a Value) -> &'a Value {
+    match node.get("$ref") {
+        Some(reference) => resolve_ref(reference, root),
+        None => node,
+    }
+}
+
+fn resolve_ref<'a>(reference: &Value, root: &'a Value) -> &'a Value {
+    reference
+        .as_str()
+        .and_then(|r| r.strip_prefix("#/$defs/"))
+        .and_then(|name| root.get("$defs").and_then(|defs| defs.get(name)))
+        .unwrap_or(&Value::Null)
+}
+
+/// Returns the candidate most similar to `key`, if it is close enough to be a typo.
+fn suggest<'a>(
+    key: &str,
+    candidates: impl Iterator<Item = &'a String>,
+) -> Option<String> {
+    let threshold = std::cmp::max(2, key.len() / 3);
+    candidates
+        .map(|candidate| (strsim::damerau_levenshtein(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn accepts_known_keys() {
+        let raw = json!({
+            "schemaVersion": "v1",
+            "template_suffix": ".j2",
+            "questions": {
+                "name": {
+                    "type": "str",
+                    "secret": {"confirm": true},
+                    "validation": {"condition": "name", "error_message": "required"}
+                },
+                "slug": {
+                    "type": "str",
+                    "validation": [{"regex": "^[a-z]+$"}, {"min_length": 2, "not_in": ["x"]}]
+                }
+            }
+        });
+        assert!(find_unknown_keys(&raw).is_empty());
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_suggestions() {
+        let raw = json!({
+            "schemaVersion": "v1",
+            "template_sufix": ".j2",
+            "questions": {
+                "name": {
+                    "type": "str",
+                    "ask-if": "true",
+                    "secret": {"mismatch_err": "nope"},
+                    "colour": "blue",
+                    "validation": [{"regex": "^a"}, {"min_lenght": 3}]
+                }
+            }
+        });
+        let unknown = find_unknown_keys(&raw);
+        let mut rendered: Vec<String> = unknown.iter().map(ToString::to_string).collect();
+        rendered.sort();
+        assert_eq!(
+            rendered,
+            vec![
+                "unknown key 'questions.name.ask-if' (did you mean 'ask_if'?)",
+                "unknown key 'questions.name.colour'",
+                "unknown key 'questions.name.secret.mismatch_err' (did you mean 'mistmatch_err'?)",
+                "unknown key 'questions.name.validation[1].min_lenght' (did you mean 'min_length'?)",
+                "unknown key 'template_sufix' (did you mean 'template_suffix'?)",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_keys_against_the_declared_version() {
+        let v1 = json!({
+            "schemaVersion": "v1",
+            "hooks": {"pre": {"filename": "pre.sh"}},
+            "questions": {"name": {"type": "str", "secret": {"mistmatch_err": "x"}}}
+        });
+        let rendered: Vec<String> =
+            find_unknown_keys(&v1).iter().map(ToString::to_string).collect();
+        assert_eq!(rendered, vec!["unknown key 'hooks'"]);
+
+        let v2 = json!({
+            "schemaVersion": "v2",
+            "post_hook_filename": "post.sh",
+            "hooks": {"post": {"print_stdout": true}},
+            "questions": {"name": {"type": "str", "secret": {"mistmatch_err": "x"}}}
+        });
+        let mut rendered: Vec<String> =
+            find_unknown_keys(&v2).iter().map(ToString::to_string).collect();
+        rendered.sort();
+        assert_eq!(
+            rendered,
+            vec![
+                "unknown key 'post_hook_filename'",
+                "unknown key 'questions.name.secret.mistmatch_err' (did you mean 'mismatch_error'?)",
+            ]
+        );
+    }
+
+    #[test]
+    fn committed_schema_is_up_to_date() {
+        let committed: Value =
+            serde_json::from_str(include_str!("../../docs/baker.schema.json")).unwrap();
+        assert_eq!(
+            committed,
+            config_schema(),
+            "docs/baker.schema.json is stale; regenerate it with `baker schema > docs/baker.schema.json`"
+        );
+    }
+}
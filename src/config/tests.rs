@@ -5,7 +5,7 @@ mod config_tests {
     use serde_json::json;
 
     use crate::config::question::{Question, QuestionRendered};
-    use crate::config::types::{get_default_validation, Type};
+    use crate::config::types::{get_default_validation, Choice, Type};
     use crate::config::QuestionType;
     use crate::template::get_template_engine;
 
@@ -21,7 +21,14 @@ mod config_tests {
             choices: vec![],
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         };
         let engine = get_template_engine();
 
@@ -46,16 +53,19 @@ mod config_tests {
             ask_if: "".to_string(),
             secret: None,
             multiselect: true,
-            choices: vec![
-                "Python".to_string(),
-                "Django".to_string(),
-                "FastAPI".to_string(),
-                "Next.JS".to_string(),
-                "TypeScript".to_string(),
-            ],
+            choices: ["Python", "Django", "FastAPI", "Next.JS", "TypeScript"]
+                .map(|choice| Choice::from(choice.to_string()))
+                .to_vec(),
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         };
         let engine = get_template_engine();
 
@@ -83,7 +93,14 @@ mod config_tests {
             choices: vec![],
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         };
         let engine = get_template_engine();
 
@@ -107,7 +124,14 @@ mod config_tests {
             choices: vec![],
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         };
         let engine = get_template_engine();
 
@@ -131,7 +155,14 @@ mod config_tests {
             choices: vec![],
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         };
         let engine = get_template_engine();
 
@@ -156,7 +187,14 @@ mod config_tests {
             choices: vec![],
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         };
         let engine = get_template_engine();
 
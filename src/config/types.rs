@@ -1,10 +1,11 @@
 //! Basic types and enums for configuration
 
 use crate::constants::validation;
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer};
 
 /// Type of question to be presented to the user
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Type {
     /// String input question type
@@ -15,23 +16,193 @@ pub enum Type {
     Json,
     /// YAML structured input type
     Yaml,
+    /// Filesystem path input type
+    Path,
+    /// URL input type
+    Url,
+    /// Calendar date input type
+    Date,
 }
 
-#[derive(Debug, Deserialize)]
+/// Options for secret (password) questions
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Secret {
     /// Whether the secret should have confirmation
     #[serde(default)]
     pub confirm: bool,
+    /// Error shown when the confirmation does not match
+    #[serde(default = "get_default_mismatch_error")]
+    pub mismatch_error: String,
+}
+
+/// Options for secret (password) questions in `schemaVersion: v1`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SecretV1 {
+    /// Whether the secret should have confirmation
+    #[serde(default)]
+    pub confirm: bool,
+    /// Error shown when the confirmation does not match
     #[serde(default = "get_default_mismatch_error")]
     pub mistmatch_err: String,
 }
 
-#[derive(Debug, Deserialize)]
+impl From<SecretV1> for Secret {
+    fn from(secret: SecretV1) -> Self {
+        Self { confirm: secret.confirm, mismatch_error: secret.mistmatch_err }
+    }
+}
+
+/// A choice offered by a single or multiple choice question
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+#[serde(from = "ChoiceDef")]
+pub struct Choice {
+    /// Value stored as the answer when the choice is picked
+    pub value: serde_json::Value,
+    /// Text shown for the choice in the prompt
+    pub label: String,
+}
+
+impl Choice {
+    /// Whether `answer` selects this choice, either by value or by the value's
+    /// text form (e.g. `"8080"` for the choice `8080`).
+    pub fn matches(&self, answer: &serde_json::Value) -> bool {
+        self.value == *answer
+            || answer.as_str() == Some(choice_text(&self.value).as_str())
+    }
+}
+
+impl From<String> for Choice {
+    fn from(value: String) -> Self {
+        Self { label: value.clone(), value: serde_json::Value::String(value) }
+    }
+}
+
+/// How a choice is written in the configuration: a plain value, or a value with a label
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ChoiceDef {
+    /// A value shown with its own label
+    Labeled {
+        /// Value stored as the answer
+        value: ChoiceValue,
+        /// Text shown in the prompt instead of the value
+        #[serde(default)]
+        label: Option<String>,
+    },
+    /// A value shown as is
+    Plain(ChoiceValue),
+}
+
+/// Scalar value of a choice
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ChoiceValue {
+    /// A boolean choice
+    Bool(bool),
+    /// An integer choice
+    Integer(i64),
+    /// A floating point choice
+    Float(f64),
+    /// A string choice
+    String(String),
+}
+
+impl From<ChoiceDef> for Choice {
+    fn from(def: ChoiceDef) -> Self {
+        let (value, label) = match def {
+            ChoiceDef::Labeled { value, label } => (value, label),
+            ChoiceDef::Plain(value) => (value, None),
+        };
+        let value = match value {
+            ChoiceValue::Bool(b) => serde_json::Value::from(b),
+            ChoiceValue::Integer(i) => serde_json::Value::from(i),
+            ChoiceValue::Float(f) => serde_json::Value::from(f),
+            ChoiceValue::String(s) => serde_json::Value::from(s),
+        };
+        let label = label.unwrap_or_else(|| choice_text(&value));
+        Self { value, label }
+    }
+}
+
+/// Text form of a choice value: strings as is, other scalars as JSON.
+pub fn choice_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Options for path questions
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct PathOptions {
+    /// Whether the path must already exist, relative to the current directory
+    #[serde(default)]
+    pub must_exist: bool,
+    /// File extensions the path must end with, e.g. `[toml, yaml]`
+    #[serde(default)]
+    pub extensions: Vec<String>,
+}
+
+/// Validation rule applied to an answer
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Validation {
+    /// Expression that must evaluate to true for the answer to be accepted
     #[serde(default = "get_default_condition")]
     pub condition: String,
+    /// Error message (a template rendered with `value` and the other answers) shown
+    /// when the rule fails
     #[serde(default = "get_default_error_message")]
     pub error_message: String,
+    /// Regular expression a string answer must match
+    #[serde(default)]
+    pub regex: Option<String>,
+    /// Minimum number of characters (or items for lists)
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    /// Maximum number of characters (or items for lists)
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Values the answer (or every item of a list answer) must be one of
+    #[serde(default)]
+    pub one_of: Option<Vec<serde_json::Value>>,
+    /// Values the answer (or any item of a list answer) must not be
+    #[serde(default)]
+    pub not_in: Option<Vec<serde_json::Value>>,
+}
+
+/// Either a single item or a list of items, as accepted by `validation`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    /// A single item
+    One(T),
+    /// A list of items
+    Many(Vec<T>),
+}
+
+/// Deserializes a single rule or a list of rules into a list.
+pub fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(item) => vec![item],
+        OneOrMany::Many(items) => items,
+    })
+}
+
+/// Validation rule evaluated over the full answer set once every question is answered
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrossValidation {
+    /// Expression over all answers that must evaluate to true
+    pub condition: String,
+    /// Error message (a template) shown when the condition fails
+    #[serde(default = "get_default_error_message")]
+    pub error_message: String,
+    /// Questions to re-ask when the rule fails. Defaults to the questions named in `condition`
+    #[serde(default)]
+    pub questions: Vec<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -42,6 +213,9 @@ pub enum QuestionType {
     Boolean,
     Json,
     Yaml,
+    Path,
+    Url,
+    Date,
 }
 
 fn get_default_error_message() -> String {
@@ -60,6 +234,11 @@ pub fn get_default_validation() -> Validation {
     Validation {
         condition: get_default_condition(),
         error_message: get_default_error_message(),
+        regex: None,
+        min_length: None,
+        max_length: None,
+        one_of: None,
+        not_in: None,
     }
 }
 
@@ -67,6 +246,23 @@ pub fn get_default_validation() -> Validation {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_choices_accept_scalars_and_labels() {
+        let choices: Vec<Choice> = serde_yaml::from_str(
+            "- postgres\n- 8080\n- value: true\n  label: Enabled\n- value: 1.5",
+        )
+        .unwrap();
+
+        assert_eq!(choices[0], Choice::from("postgres".to_string()));
+        assert_eq!(choices[1].value, serde_json::json!(8080));
+        assert_eq!(choices[1].label, "8080");
+        assert_eq!(choices[2].value, serde_json::json!(true));
+        assert_eq!(choices[2].label, "Enabled");
+        assert_eq!(choices[3].label, "1.5");
+        assert!(choices[1].matches(&serde_json::json!("8080")));
+        assert!(!choices[1].matches(&serde_json::json!(80)));
+    }
+
     #[test]
     fn test_default_values() {
         assert_eq!(get_default_error_message(), validation::INVALID_ANSWER);
@@ -1,12 +1,22 @@
 //! Conflict marker types and utilities for merging file content.
 
 use clap::ValueEnum;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Style to use when writing conflict markers into a file.
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum, Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+    Default,
+    JsonSchema,
 )]
 #[serde(rename_all = "lowercase")]
 #[value(rename_all = "lowercase")]
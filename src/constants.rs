@@ -1,7 +1,11 @@
 //! Constants used throughout the Baker application
 
 /// Configuration file names in order of preference
-pub const CONFIG_FILENAMES: &[&str] = &["baker.json", "baker.yaml", "baker.yml"];
+pub const CONFIG_FILENAMES: &[&str] =
+    &["baker.json", "baker.yaml", "baker.yml", "baker.toml"];
+
+/// Configuration file of cookiecutter templates, used when no Baker configuration exists
+pub const COOKIECUTTER_CONFIG_FILENAME: &str = "cookiecutter.json";
 
 /// Default template file suffix
 pub const DEFAULT_TEMPLATE_SUFFIX: &str = ".baker.j2";
@@ -27,11 +31,28 @@ pub const DEFAULT_GENERATED_FILE_NAME: &str = ".baker-generated.yaml";
 /// STDIN indicator for CLI arguments
 pub const STDIN_INDICATOR: &str = "-";
 
+/// Environment variable overriding the location of the user defaults file
+pub const USER_CONFIG_ENV: &str = "BAKER_USER_CONFIG";
+
+/// Environment variable overriding the directory replay answers are stored in
+pub const REPLAY_DIR_ENV: &str = "BAKER_REPLAY_DIR";
+
+/// Prefix of environment variables that answer questions, e.g. `BAKER_ANSWER_NAME`
+pub const ANSWER_ENV_PREFIX: &str = "BAKER_ANSWER_";
+
+/// Name under which `transform` and `validation` expressions see the answer being
+/// checked; no question may use it as its key
+pub const ANSWER_VALUE_NAME: &str = "value";
+
+/// Default `strftime`-style format of date answers
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
 /// JSON Schema validation messages
 pub mod validation {
     pub const INVALID_ANSWER: &str = "Invalid answer";
     pub const PASSWORDS_MISMATCH: &str = "Passwords do not match";
     pub const DEFAULT_CONDITION: &str = "true";
+    pub const EMPTY_ANSWER: &str = "Answer cannot be empty";
 }
 
 /// Exit codes
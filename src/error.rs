@@ -24,6 +24,9 @@ pub enum Error {
     #[error("Failed to parse YAML: {0}")]
     YAMLParseError(#[from] serde_yaml::Error),
 
+    #[error("Failed to parse TOML: {0}")]
+    TOMLParseError(#[from] toml::de::Error),
+
     #[error("Failed to parse glob pattern in .bakerignore file: {0}")]
     GlobSetParseError(#[from] globset::Error),
 
@@ -39,9 +42,27 @@ pub enum Error {
     #[error("Template rendering failed: {0}")]
     MinijinjaError(#[from] minijinja::Error),
 
+    #[cfg(feature = "handlebars")]
+    #[error("Template rendering failed: {0}")]
+    HandlebarsRenderError(#[from] handlebars::RenderError),
+
+    #[cfg(feature = "handlebars")]
+    #[error("Template compilation failed: {0}")]
+    HandlebarsTemplateError(#[from] handlebars::TemplateError),
+
+    #[cfg(feature = "tera")]
+    #[error("Template rendering failed: {0}")]
+    TeraError(String),
+
+    #[error("The '{engine}' template engine does not support {feature}")]
+    UnsupportedByEngine { engine: String, feature: String },
+
     #[error("Hook script '{script}' failed with exit code: {status}")]
     HookExecutionError { script: String, status: ExitStatus },
 
+    #[error("Command '{command}' failed with exit code: {status}")]
+    CommandExecutionError { command: String, status: ExitStatus },
+
     #[error(
         "Output directory '{output_dir}' already exists. Use --force to overwrite it."
     )]
@@ -53,6 +74,14 @@ pub enum Error {
     #[error("Cannot process path '{source_path}': {e}")]
     ProcessError { source_path: String, e: String },
 
+    #[error(
+        "Failed to handle file operation for template '{template}' ({operation}): {e}"
+    )]
+    FileOperationError { template: String, operation: String, e: String },
+
+    #[error("{} file(s) failed to generate:\n{}", .failures.len(), .failures.join("\n"))]
+    GenerationFailed { failures: Vec<String> },
+
     #[error(
         "Generated metadata file not found at '{path}'. Run 'baker generate' first."
     )]
@@ -61,9 +90,32 @@ pub enum Error {
     #[error("Unsupported generated metadata version '{found}'. Expected '1'.")]
     UnsupportedGeneratedVersion { found: String },
 
+    #[error("No saved answers to replay for template '{template}'")]
+    ReplayNotFound { template: String },
+
     #[error("Answers JSON is not an object")]
     AnswersNotObject,
 
+    #[error("Template inheritance cycle detected: {chain}")]
+    TemplateInheritanceCycle { chain: String },
+
+    #[error("Answer validation failed: {}", .messages.join("; "))]
+    AnswersValidationError { messages: Vec<String> },
+
+    #[error("Missing answers in non-interactive mode:\n{}", .questions.join("\n"))]
+    MissingAnswers { questions: Vec<String> },
+
+    #[error("Cannot transform the answer to '{question}': {e}")]
+    AnswerTransformError { question: String, e: String },
+
+    #[error(
+        "Cannot migrate schemaVersion '{found}'. Only v1 configurations can be migrated."
+    )]
+    UnsupportedConfigVersion { found: String },
+
+    #[error("Template lint failed with {count} issue(s)")]
+    LintFailed { count: usize },
+
     #[error("{0}")]
     Other(#[from] anyhow::Error),
 }
@@ -97,5 +149,8 @@ mod tests {
 
         let err = Error::AnswersNotObject;
         assert_eq!(err.to_string(), "Answers JSON is not an object");
+
+        let err = Error::TemplateInheritanceCycle { chain: "a -> b -> a".to_string() };
+        assert_eq!(err.to_string(), "Template inheritance cycle detected: a -> b -> a");
     }
 }
@@ -1,7 +1,7 @@
 //! Generated metadata file — written to the output directory after every generate run.
 
 use crate::{
-    config::ConfigV1, constants::DEFAULT_GENERATED_FILE_NAME, error::Result,
+    config::ConfigV2, constants::DEFAULT_GENERATED_FILE_NAME, error::Result,
     loader::TemplateSourceInfo,
 };
 use chrono::Utc;
@@ -17,8 +17,19 @@ pub struct BakerGenerated {
     pub generated_at: String,
     /// Information about the template source (local path + hash, or git URL + commit/tag).
     pub template: TemplateSourceInfo,
+    /// Parent templates pulled in through `extends`, nearest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extends: Vec<TemplateSourceInfo>,
     /// The answers collected during generation, serialised as a JSON value.
     pub answers: serde_json::Value,
+    /// Seed of the random template functions, reused by `baker update`. Only recorded
+    /// when it was chosen with `--random-seed`, since it reveals every random value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub random_seed: Option<u64>,
+    /// Files that failed to generate, so that an incomplete project is not taken
+    /// for a complete one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
 }
 
 impl BakerGenerated {
@@ -28,9 +39,30 @@ impl BakerGenerated {
             version: "1".to_string(),
             generated_at: Utc::now().to_rfc3339(),
             template,
+            extends: Vec::new(),
             answers,
+            random_seed: None,
+            failures: Vec::new(),
         }
     }
+
+    /// Record the `extends` chain the template was layered on.
+    pub fn with_extends(mut self, extends: Vec<TemplateSourceInfo>) -> Self {
+        self.extends = extends;
+        self
+    }
+
+    /// Record the seed the random template functions were rendered with, if any.
+    pub fn with_random_seed(mut self, random_seed: Option<u64>) -> Self {
+        self.random_seed = random_seed;
+        self
+    }
+
+    /// Record the files that failed to generate.
+    pub fn with_failures(mut self, failures: &[crate::error::Error]) -> Self {
+        self.failures = failures.iter().map(ToString::to_string).collect();
+        self
+    }
 }
 
 /// Write a `BakerGenerated` record to `<output_dir>/<file_name>`.
@@ -74,15 +106,16 @@ pub fn resolve_file_name<'a>(
 /// Returns a copy of `answers` with secret (password) question keys removed.
 ///
 /// Questions that have `secret: Some(...)` are considered password fields and
-/// should not be persisted in plaintext. On `update`, these will be re-prompted.
+/// should not be persisted in plaintext, and neither are answers obtained through
+/// `secret_command`. On `update`, these will be re-prompted.
 pub fn strip_secret_answers(
     answers: &serde_json::Value,
-    config: &ConfigV1,
+    config: &ConfigV2,
 ) -> serde_json::Value {
     let mut filtered = answers.clone();
     if let Some(obj) = filtered.as_object_mut() {
         for (key, question) in &config.questions {
-            if question.secret.is_some() {
+            if question.secret.is_some() || question.secret_command.is_some() {
                 obj.remove(key);
             }
         }
@@ -187,8 +220,7 @@ questions:
     secret:
       confirm: false
 "#;
-        let config: Config = serde_yaml::from_str(raw).unwrap();
-        let Config::V1(config) = config;
+        let config = serde_yaml::from_str::<Config>(raw).unwrap().normalize();
 
         let answers = serde_json::json!({
             "name": "Alice",
@@ -214,8 +246,7 @@ questions:
     help: Your name
     default: World
 "#;
-        let config: Config = serde_yaml::from_str(raw).unwrap();
-        let Config::V1(config) = config;
+        let config = serde_yaml::from_str::<Config>(raw).unwrap().normalize();
 
         let answers = serde_json::json!({"name": "Bob"});
         let stripped = strip_secret_answers(&answers, &config);
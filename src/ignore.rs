@@ -1,7 +1,10 @@
 use crate::error::Result;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use log::{debug, info};
-use std::{fs::read_to_string, path::Path};
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
 
 /// Default patterns to always ignore during template processing
 const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
@@ -18,6 +21,7 @@ const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
     "baker.yaml",
     "baker.yml",
     "baker.json",
+    "baker.toml",
 ];
 
 /// Baker's ignore file name
@@ -25,33 +29,50 @@ pub const IGNORE_FILE: &str = ".bakerignore";
 
 /// Reads and processes the .bakerignore file to create a set of glob patterns.
 pub fn parse_bakerignore_file<P: AsRef<Path>>(template_root: P) -> Result<GlobSet> {
+    parse_layered_bakerignore_file(template_root, &[])
+}
+
+/// Like [`parse_bakerignore_file`], but also applies the `.bakerignore` patterns of
+/// every other template in an `extends` chain (`layer_roots`) to `template_root`.
+pub fn parse_layered_bakerignore_file<P: AsRef<Path>>(
+    template_root: P,
+    layer_roots: &[PathBuf],
+) -> Result<GlobSet> {
     let mut builder = GlobSetBuilder::new();
+    // Patterns are anchored at the template root, whose own path may contain glob
+    // metacharacters, e.g. a `{{cookiecutter.project_slug}}` directory.
     let template_root = template_root.as_ref();
-    let bakerignore_path = template_root.join(IGNORE_FILE);
+    let escaped_root = globset::escape(&template_root.to_string_lossy());
+    let pattern_root = Path::new(&escaped_root);
 
     // Add default patterns first
     let mut patterns: Vec<String> = DEFAULT_IGNORE_PATTERNS
         .iter()
         .map(|pattern| {
-            let path_to_ignored_pattern = template_root.join(pattern);
+            let path_to_ignored_pattern = pattern_root.join(pattern);
             path_to_ignored_pattern.to_string_lossy().to_string()
         })
         .collect();
 
-    // Then add patterns from .bakerignore if it exists
-    if let Ok(contents) = read_to_string(bakerignore_path) {
-        let ignored_patterns: Vec<String> = contents
-            .lines()
-            .map(|line| line.trim())
-            .filter(|line| !line.is_empty() && !line.starts_with('#'))
-            .map(|line| {
-                let path_to_ignored_pattern = template_root.join(line);
-                path_to_ignored_pattern.to_string_lossy().to_string()
-            })
-            .collect();
-        patterns.extend(ignored_patterns);
-    } else {
-        debug!("No .bakerignore file found, using default patterns.");
+    // Then add patterns from each .bakerignore that exists
+    for ignore_root in
+        std::iter::once(template_root).chain(layer_roots.iter().map(|p| p.as_path()))
+    {
+        let bakerignore_path = ignore_root.join(IGNORE_FILE);
+        if let Ok(contents) = read_to_string(bakerignore_path) {
+            let ignored_patterns: Vec<String> = contents
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| {
+                    let path_to_ignored_pattern = pattern_root.join(line);
+                    path_to_ignored_pattern.to_string_lossy().to_string()
+                })
+                .collect();
+            patterns.extend(ignored_patterns);
+        } else {
+            debug!("No .bakerignore file found in '{}'.", ignore_root.display());
+        }
     }
 
     for pattern in &patterns {
@@ -78,6 +99,7 @@ mod tests {
         let ds_store = dir.path().join("foo/.DS_Store");
         let baker_yaml = dir.path().join("baker.yaml");
         let baker_yml = dir.path().join("baker.yml");
+        let baker_toml = dir.path().join("baker.toml");
         let bakerignore = dir.path().join(".bakerignore");
         let unignored = dir.path().join("src/main.rs");
 
@@ -85,6 +107,7 @@ mod tests {
         assert!(globset.is_match(&ds_store));
         assert!(globset.is_match(&baker_yaml));
         assert!(globset.is_match(&baker_yml));
+        assert!(globset.is_match(&baker_toml));
         assert!(globset.is_match(&bakerignore));
         assert!(!globset.is_match(&unignored));
     }
@@ -115,4 +138,20 @@ mod tests {
         assert!(!globset.is_match(&normal_file));
         assert!(globset.is_match(&baker_yaml));
     }
+
+    #[test]
+    fn parse_layered_bakerignore_file_applies_patterns_from_all_layers() {
+        let child = tempdir().unwrap();
+        let parent = tempdir().unwrap();
+        std::fs::write(child.path().join(IGNORE_FILE), "LICENSE\n").unwrap();
+        std::fs::write(parent.path().join(IGNORE_FILE), "*.bak\n").unwrap();
+
+        let globset =
+            parse_layered_bakerignore_file(parent.path(), &[child.path().to_path_buf()])
+                .unwrap();
+
+        assert!(globset.is_match(parent.path().join("LICENSE")));
+        assert!(globset.is_match(parent.path().join("old.bak")));
+        assert!(!globset.is_match(parent.path().join("README.md")));
+    }
 }
@@ -35,6 +35,12 @@ pub mod error;
 /// Generated metadata file support.
 pub mod generated;
 
+/// Per-user default answers.
+pub mod user_config;
+
+/// Last answers per template, reused by `--replay`.
+pub mod replay;
+
 /// Processes .bakerignore files to exclude specific paths.
 pub mod ignore;
 
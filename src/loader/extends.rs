@@ -0,0 +1,226 @@
+//! Template inheritance — resolves the chain of parent templates named by `extends`.
+
+use crate::{
+    config::{Config, ConfigV2},
+    error::{Error, Result},
+    loader::{get_template, git::GitLoader, LoadedTemplate, TemplateSourceInfo},
+};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// A parent template pulled in through `extends`.
+#[derive(Debug)]
+pub struct ParentTemplate {
+    /// Path to the parent template directory on disk.
+    pub root: PathBuf,
+    /// Metadata about where the parent template came from.
+    pub source: TemplateSourceInfo,
+    /// The parent's own configuration. Its questions are moved into the child's
+    /// merged configuration, so only settings such as the import root remain.
+    pub config: ConfigV2,
+}
+
+/// The resolved `extends` chain of a template.
+///
+/// Parents are ordered from the nearest parent to the base-most ancestor. Git parents
+/// are cloned into a temporary directory that lives as long as this value.
+#[derive(Debug, Default)]
+pub struct TemplateChain {
+    pub parents: Vec<ParentTemplate>,
+    clones: Option<TempDir>,
+}
+
+impl TemplateChain {
+    /// Root directories of all parents, nearest first.
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.parents.iter().map(|parent| parent.root.clone()).collect()
+    }
+
+    /// Source metadata of all parents, nearest first.
+    pub fn sources(&self) -> Vec<TemplateSourceInfo> {
+        self.parents.iter().map(|parent| parent.source.clone()).collect()
+    }
+}
+
+/// Resolves the `extends` chain of `config` and merges the parents' questions and
+/// answer validations into it.
+///
+/// Questions are layered so that the base-most template's questions come first and a
+/// question redefined by a descendant replaces the inherited one in place.
+///
+/// # Arguments
+/// * `template_root` - Directory of the template that owns `config`
+/// * `config` - The child configuration; its `questions` are replaced with the merged set
+///
+/// # Returns
+/// * `Result<TemplateChain>` - The resolved parents, nearest first
+pub fn resolve_extends(
+    template_root: &Path,
+    config: &mut ConfigV2,
+) -> Result<TemplateChain> {
+    let mut chain = TemplateChain::default();
+    let mut visited = vec![filesystem_key(template_root)];
+    let mut current_root = template_root.to_path_buf();
+    let mut next = config.settings.extends.clone();
+
+    while let Some(parent_ref) = next {
+        let loaded = load_parent(&parent_ref, &current_root, &mut chain)?;
+
+        let key = match &loaded.source {
+            TemplateSourceInfo::Git { url, .. } => url.clone(),
+            TemplateSourceInfo::Filesystem { .. } => filesystem_key(&loaded.root),
+        };
+        if visited.contains(&key) {
+            visited.push(key);
+            return Err(Error::TemplateInheritanceCycle { chain: visited.join(" -> ") });
+        }
+        visited.push(key);
+
+        let mut parent_config = Config::load(&loaded.root)?;
+        parent_config.validate()?;
+        log::debug!("Template extends '{}' ({})", parent_ref, loaded.root.display());
+
+        let mut questions = std::mem::take(&mut parent_config.questions);
+        for (name, question) in std::mem::take(&mut config.questions) {
+            questions.insert(name, question);
+        }
+        config.questions = questions;
+
+        let mut validations = std::mem::take(&mut parent_config.settings.validations);
+        validations.append(&mut config.settings.validations);
+        config.settings.validations = validations;
+
+        next = parent_config.settings.extends.clone();
+        current_root = loaded.root.clone();
+        chain.parents.push(ParentTemplate {
+            root: loaded.root,
+            source: loaded.source,
+            config: parent_config,
+        });
+    }
+
+    Ok(chain)
+}
+
+/// Loads a single parent template. Relative local paths are resolved against the
+/// directory of the template that extends it; git parents are cloned into the
+/// chain's temporary directory.
+fn load_parent(
+    parent_ref: &str,
+    child_root: &Path,
+    chain: &mut TemplateChain,
+) -> Result<LoadedTemplate> {
+    if GitLoader::<&str>::is_git_url(parent_ref) {
+        let clones = match &chain.clones {
+            Some(dir) => dir.path().to_path_buf(),
+            None => {
+                let dir = TempDir::new()?;
+                let path = dir.path().to_path_buf();
+                chain.clones = Some(dir);
+                path
+            }
+        };
+        let parent_dir = clones.join(chain.parents.len().to_string());
+        std::fs::create_dir_all(&parent_dir)?;
+        return GitLoader::new(parent_ref, true).load_into_parent(&parent_dir);
+    }
+
+    let path = Path::new(parent_ref);
+    let path =
+        if path.is_relative() { child_root.join(path) } else { path.to_path_buf() };
+    get_template(&path.to_string_lossy(), true)
+}
+
+fn filesystem_key(path: &Path) -> String {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf()).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn write_config(dir: &Path, content: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("baker.yaml"), content).unwrap();
+    }
+
+    fn load(dir: &Path) -> ConfigV2 {
+        Config::load(dir).unwrap()
+    }
+
+    #[test]
+    fn merges_parent_questions_under_child() {
+        let tmp = tempdir().unwrap();
+        let base = tmp.path().join("base");
+        let child = tmp.path().join("child");
+        write_config(
+            &base,
+            r#"schemaVersion: v1
+questions:
+  license:
+    type: str
+    default: MIT
+  author:
+    type: str
+"#,
+        );
+        write_config(
+            &child,
+            r#"schemaVersion: v1
+extends: ../base
+questions:
+  project_name:
+    type: str
+  license:
+    type: str
+    default: Apache-2.0
+"#,
+        );
+
+        let mut config = load(&child);
+        let chain = resolve_extends(&child, &mut config).unwrap();
+
+        assert_eq!(chain.parents.len(), 1);
+        let keys: Vec<&str> = config.questions.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["license", "author", "project_name"]);
+        assert_eq!(config.questions["license"].default, "Apache-2.0");
+    }
+
+    #[test]
+    fn resolves_multi_level_chain_nearest_first() {
+        let tmp = tempdir().unwrap();
+        write_config(
+            &tmp.path().join("a"),
+            "schemaVersion: v1\nquestions:\n  a:\n    type: str\n",
+        );
+        write_config(
+            &tmp.path().join("b"),
+            "schemaVersion: v1\nextends: ../a\nquestions:\n  b:\n    type: str\n",
+        );
+        let child = tmp.path().join("c");
+        write_config(&child, "schemaVersion: v1\nextends: ../b\nquestions: {}\n");
+
+        let mut config = load(&child);
+        let chain = resolve_extends(&child, &mut config).unwrap();
+
+        let roots = chain.roots();
+        assert!(roots[0].ends_with("b"));
+        assert!(roots[1].ends_with("a"));
+        assert_eq!(config.questions.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn detects_inheritance_cycles() {
+        let tmp = tempdir().unwrap();
+        let a = tmp.path().join("a");
+        write_config(&a, "schemaVersion: v1\nextends: ../b\n");
+        write_config(&tmp.path().join("b"), "schemaVersion: v1\nextends: ../a\n");
+
+        let mut config = load(&a);
+        let err = resolve_extends(&a, &mut config).unwrap_err();
+
+        assert!(matches!(err, Error::TemplateInheritanceCycle { .. }));
+    }
+}
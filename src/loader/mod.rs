@@ -4,6 +4,7 @@ use crate::loader::{git::GitLoader, local::LocalLoader};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+pub mod extends;
 pub mod git;
 pub mod interface;
 pub mod local;
@@ -50,6 +51,62 @@ pub enum TemplateSourceInfo {
     },
 }
 
+impl TemplateSourceInfo {
+    /// The git URL or filesystem path the template was loaded from.
+    pub fn location(&self) -> &str {
+        match self {
+            TemplateSourceInfo::Filesystem { path, .. } => path,
+            TemplateSourceInfo::Git { url, .. } => url,
+        }
+    }
+
+    /// The location in a form that is the same however the template was referred
+    /// to, used to key per-template data such as replay files and user defaults.
+    /// Local paths are canonicalized (when they exist); git URLs get a lower-case
+    /// host and lose a trailing `/` and `.git`.
+    pub fn canonical_location(&self) -> String {
+        match self {
+            TemplateSourceInfo::Filesystem { path, .. } => canonical_path(path),
+            TemplateSourceInfo::Git { url, .. } => normalize_git_url(url),
+        }
+    }
+}
+
+/// Returns the canonical form of a template path or git URL as given on the command
+/// line, see [`TemplateSourceInfo::canonical_location`]. Relative paths are
+/// resolved against the current directory.
+pub fn canonical_template_location(location: &str) -> String {
+    if GitLoader::<&str>::is_git_url(location) {
+        normalize_git_url(location)
+    } else {
+        canonical_path(location)
+    }
+}
+
+fn canonical_path(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+/// Normalizes a git URL, see [`TemplateSourceInfo::canonical_location`].
+fn normalize_git_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    if url.contains("://") {
+        if let Ok(parsed) = url::Url::parse(url) {
+            return parsed.as_str().trim_end_matches('/').to_string();
+        }
+    }
+    // scp-like `user@host:path`
+    match url.split_once('@').and_then(|(user, rest)| {
+        rest.split_once(':').map(|(host, path)| (user, host, path))
+    }) {
+        Some((user, host, path)) => format!("{user}@{}:{path}", host.to_lowercase()),
+        None => url.to_string(),
+    }
+}
+
 /// The result of loading a template: the on-disk path plus source metadata.
 #[derive(Debug)]
 pub struct LoadedTemplate {
@@ -95,6 +152,36 @@ mod tests {
         assert_eq!(format!("{git_source}"), "git repository: 'git@github.com:user/repo'");
     }
 
+    #[test]
+    fn canonical_location_normalizes_paths_and_git_urls() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(tmp.path().join("tpl")).unwrap();
+        let filesystem = |path: PathBuf| TemplateSourceInfo::Filesystem {
+            path: path.to_string_lossy().to_string(),
+            hash: String::new(),
+        };
+        let canonical = filesystem(tmp.path().join("tpl")).canonical_location();
+        assert_eq!(filesystem(tmp.path().join("./tpl/")).canonical_location(), canonical);
+        assert_eq!(
+            filesystem(tmp.path().join("tpl/../tpl")).canonical_location(),
+            canonical
+        );
+
+        let git = |url: &str| TemplateSourceInfo::Git {
+            url: url.to_string(),
+            commit: String::new(),
+            tag: None,
+        };
+        assert_eq!(
+            git("https://GitHub.com/user/repo.git/").canonical_location(),
+            "https://github.com/user/repo"
+        );
+        assert_eq!(
+            git("git@GitHub.com:user/repo.git").canonical_location(),
+            "git@github.com:user/repo"
+        );
+    }
+
     #[test]
     fn test_get_template_uses_local_loader_for_filesystem_paths() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -1,5 +1,8 @@
 use baker::{
-    cli::{get_args, get_log_level_from_verbose, run, run_update, Commands},
+    cli::{
+        get_args, get_log_level_from_verbose, run, run_lint, run_migrate_config,
+        run_questions, run_schema, run_update, Commands,
+    },
     error::default_error_handler,
 };
 
@@ -11,6 +14,10 @@ fn main() {
     let result = match args.command {
         Commands::Generate(generate_args) => run(generate_args),
         Commands::Update(update_args) => run_update(update_args),
+        Commands::Lint(lint_args) => run_lint(lint_args),
+        Commands::Schema => run_schema(),
+        Commands::Questions(questions_args) => run_questions(questions_args),
+        Commands::MigrateConfig(migrate_args) => run_migrate_config(migrate_args),
     };
 
     if let Err(err) = result {
@@ -37,7 +37,14 @@ mod tests {
             ask_if: "true".to_string(),
             schema: None,
             schema_file: None,
-            validation: crate::config::types::get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![crate::config::types::get_default_validation()],
         };
         let default = serde_json::Value::String("default_value".to_string());
         let help = "This is a help message";
@@ -8,8 +8,9 @@ use super::interface::{
     StructuredDataConfig, TextPromptConfig,
 };
 use crate::{error::Result, prompt::parser::DataParser};
-use dialoguer::{Confirm, Editor, Input, MultiSelect, Password, Select};
+use dialoguer::{Completion, Confirm, Editor, Input, MultiSelect, Password, Select};
 use serde_json::Value;
+use std::path::Path;
 
 /// Default terminal-backed prompt provider implemented with `dialoguer`.
 pub struct DialoguerPrompter;
@@ -34,6 +35,7 @@ impl super::interface::TextPrompter for DialoguerPrompter {
             self.prompt_regular_text(
                 &config.prompt,
                 config.default.as_deref().unwrap_or(""),
+                config.complete_paths,
             )
         }
     }
@@ -125,12 +127,18 @@ impl DialoguerPrompter {
         Ok(password.interact()?)
     }
 
-    /// Handle regular text input
-    fn prompt_regular_text(&self, prompt: &str, default: &str) -> Result<String> {
-        Ok(Input::new()
-            .with_prompt(prompt)
-            .default(default.to_string())
-            .interact_text()?)
+    /// Handle regular text input, optionally completing filesystem paths
+    fn prompt_regular_text(
+        &self,
+        prompt: &str,
+        default: &str,
+        complete_paths: bool,
+    ) -> Result<String> {
+        let mut input = Input::new().with_prompt(prompt).default(default.to_string());
+        if complete_paths {
+            input = input.completion_with(&PathCompletion);
+        }
+        Ok(input.interact_text()?)
     }
 
     /// Handle terminal input for structured data
@@ -162,3 +170,91 @@ impl DialoguerPrompter {
         DataParser::parse_structured_content(&content, is_yaml)
     }
 }
+
+/// Completes the last component of a filesystem path to the longest prefix shared
+/// by the matching directory entries. Directories get a trailing separator.
+struct PathCompletion;
+
+impl Completion for PathCompletion {
+    fn get(&self, input: &str) -> Option<String> {
+        let (dir, prefix) = match input.rfind(std::path::MAIN_SEPARATOR) {
+            Some(index) => input.split_at(index + 1),
+            None => ("", input),
+        };
+        let dir_path = if dir.is_empty() { Path::new(".") } else { Path::new(dir) };
+
+        let mut matches: Vec<String> = std::fs::read_dir(dir_path)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                if !name.starts_with(prefix) {
+                    return None;
+                }
+                let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+                Some(if is_dir {
+                    format!("{name}{}", std::path::MAIN_SEPARATOR)
+                } else {
+                    name
+                })
+            })
+            .collect();
+        matches.sort();
+
+        let first = matches.first()?;
+        let common_len = matches
+            .iter()
+            .skip(1)
+            .map(|name| common_prefix_len(first, name))
+            .fold(first.len(), usize::min);
+        let completed = &first[..common_len];
+        (completed.len() > prefix.len()).then(|| format!("{dir}{completed}"))
+    }
+}
+
+/// Length in bytes of the longest common prefix of `a` and `b`.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .take_while(|((_, x), y)| x == y)
+        .map(|((i, x), _)| i + x.len_utf8())
+        .last()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_completion_completes_unique_entry() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("templates")).unwrap();
+        std::fs::write(tmp.path().join("readme.md"), "").unwrap();
+        let base = format!("{}{}", tmp.path().display(), std::path::MAIN_SEPARATOR);
+
+        assert_eq!(
+            PathCompletion.get(&format!("{base}te")),
+            Some(format!("{base}templates{}", std::path::MAIN_SEPARATOR))
+        );
+        assert_eq!(
+            PathCompletion.get(&format!("{base}re")),
+            Some(format!("{base}readme.md"))
+        );
+    }
+
+    #[test]
+    fn test_path_completion_stops_at_common_prefix() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("config.toml"), "").unwrap();
+        std::fs::write(tmp.path().join("config.yaml"), "").unwrap();
+        let base = format!("{}{}", tmp.path().display(), std::path::MAIN_SEPARATOR);
+
+        assert_eq!(
+            PathCompletion.get(&format!("{base}c")),
+            Some(format!("{base}config."))
+        );
+        assert_eq!(PathCompletion.get(&format!("{base}config.")), None);
+        assert_eq!(PathCompletion.get(&format!("{base}missing")), None);
+    }
+}
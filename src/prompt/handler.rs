@@ -8,7 +8,7 @@ use super::interface::{
     SingleChoiceConfig, StructuredDataConfig, TextPromptConfig,
 };
 use crate::{
-    config::{IntoQuestionType, QuestionType},
+    config::{Choice, IntoQuestionType, QuestionType},
     error::Result,
     prompt::PromptContext,
 };
@@ -36,6 +36,9 @@ impl<P: PromptProvider> PromptHandler<P> {
             QuestionType::Boolean => self.prompt_confirmation(prompt_context),
             QuestionType::Json => self.prompt_structured_data(prompt_context, false),
             QuestionType::Yaml => self.prompt_structured_data(prompt_context, true),
+            QuestionType::Path | QuestionType::Url | QuestionType::Date => {
+                self.prompt_text(prompt_context)
+            }
         }
     }
 
@@ -49,7 +52,7 @@ impl<P: PromptProvider> PromptHandler<P> {
         let config = self.create_single_choice_config(prompt_context);
         let selection_index = self.provider.prompt_single_choice(&config)?;
         let selected_choice = &prompt_context.question.choices[selection_index];
-        Ok(Value::String(selected_choice.clone()))
+        Ok(selected_choice.value.clone())
     }
 
     fn prompt_multiple_choice(&self, prompt_context: &PromptContext) -> Result<Value> {
@@ -58,7 +61,7 @@ impl<P: PromptProvider> PromptHandler<P> {
 
         let selected: Vec<Value> = indices
             .iter()
-            .map(|&i| Value::String(prompt_context.question.choices[i].clone()))
+            .map(|&i| prompt_context.question.choices[i].value.clone())
             .collect();
 
         Ok(Value::Array(selected))
@@ -85,17 +88,25 @@ impl<P: PromptProvider> PromptHandler<P> {
         let default = self.value_to_default_string(prompt_context.default);
         let secret = prompt_context.question.secret.as_ref().map(|s| SecretConfig {
             confirm: s.confirm,
-            mismatch_error: if s.mistmatch_err.is_empty() {
+            mismatch_error: if s.mismatch_error.is_empty() {
                 "Mismatch".to_string()
             } else {
-                s.mistmatch_err.clone()
+                s.mismatch_error.clone()
             },
         });
 
+        let question_type = prompt_context.question.into_question_type();
+        let prompt = if question_type == QuestionType::Date {
+            format!("{} ({})", prompt_context.help, prompt_context.question.date_format())
+        } else {
+            prompt_context.help.to_string()
+        };
+
         TextPromptConfig {
-            prompt: prompt_context.help.to_string(),
+            prompt,
             default: if default.is_empty() { None } else { Some(default) },
             secret,
+            complete_paths: question_type == QuestionType::Path,
         }
     }
 
@@ -110,7 +121,7 @@ impl<P: PromptProvider> PromptHandler<P> {
 
         SingleChoiceConfig {
             prompt: prompt_context.help.to_string(),
-            choices: prompt_context.question.choices.clone(),
+            choices: self.choice_labels(&prompt_context.question.choices),
             default_index,
         }
     }
@@ -119,13 +130,13 @@ impl<P: PromptProvider> PromptHandler<P> {
         &self,
         prompt_context: &PromptContext,
     ) -> MultipleChoiceConfig {
-        let default_strings = self.extract_string_array(prompt_context.default);
+        let default_values = self.extract_array(prompt_context.default);
         let defaults = self
-            .create_choice_defaults(&prompt_context.question.choices, &default_strings);
+            .create_choice_defaults(&prompt_context.question.choices, &default_values);
 
         MultipleChoiceConfig {
             prompt: prompt_context.help.to_string(),
-            choices: prompt_context.question.choices.clone(),
+            choices: self.choice_labels(&prompt_context.question.choices),
             defaults,
         }
     }
@@ -166,36 +177,32 @@ impl<P: PromptProvider> PromptHandler<P> {
 
     fn find_default_choice_index(
         &self,
-        choices: &[String],
+        choices: &[Choice],
         default_value: &Value,
     ) -> Option<usize> {
-        match default_value {
-            Value::String(default_str) => {
-                choices.iter().position(|choice| choice == default_str)
-            }
-            _ => None,
-        }
+        choices.iter().position(|choice| choice.matches(default_value))
     }
 
-    fn extract_string_array(&self, value: &Value) -> Vec<String> {
+    fn extract_array(&self, value: &Value) -> Vec<Value> {
         match value {
-            Value::Array(arr) => arr
-                .iter()
-                .filter_map(|v| match v {
-                    Value::String(s) => Some(s.clone()),
-                    _ => None,
-                })
-                .collect(),
+            Value::Array(arr) => arr.clone(),
             _ => Vec::new(),
         }
     }
 
     fn create_choice_defaults(
         &self,
-        choices: &[String],
-        default_strings: &[String],
+        choices: &[Choice],
+        defaults: &[Value],
     ) -> Vec<bool> {
-        choices.iter().map(|choice| default_strings.contains(choice)).collect()
+        choices
+            .iter()
+            .map(|choice| defaults.iter().any(|default| choice.matches(default)))
+            .collect()
+    }
+
+    fn choice_labels(&self, choices: &[Choice]) -> Vec<String> {
+        choices.iter().map(|choice| choice.label.clone()).collect()
     }
 }
 
@@ -316,11 +323,16 @@ mod tests {
         }
     }
 
-    fn create_test_validation() -> Validation {
-        Validation {
+    fn choices(values: &[&str]) -> Vec<Choice> {
+        values.iter().map(|value| Choice::from(value.to_string())).collect()
+    }
+
+    fn create_test_validation() -> Vec<Validation> {
+        vec![Validation {
             condition: "true".to_string(),
             error_message: "Invalid answer".to_string(),
-        }
+            ..crate::config::types::get_default_validation()
+        }]
     }
 
     fn create_text_question() -> Question {
@@ -334,6 +346,13 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: None,
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
             validation: create_test_validation(),
         }
     }
@@ -347,11 +366,18 @@ mod tests {
             multiselect: false,
             secret: Some(Secret {
                 confirm: true,
-                mistmatch_err: "Passwords don't match".to_string(),
+                mismatch_error: "Passwords don't match".to_string(),
             }),
             ask_if: String::new(),
             schema: None,
             schema_file: None,
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
             validation: create_test_validation(),
         }
     }
@@ -361,12 +387,19 @@ mod tests {
             help: "Choose your favorite color".to_string(),
             r#type: Type::Str,
             default: json!("blue"),
-            choices: vec!["red".to_string(), "blue".to_string(), "green".to_string()],
+            choices: choices(&["red", "blue", "green"]),
             multiselect: false,
             secret: None,
             ask_if: String::new(),
             schema: None,
             schema_file: None,
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
             validation: create_test_validation(),
         }
     }
@@ -376,17 +409,19 @@ mod tests {
             help: "Select languages you know".to_string(),
             r#type: Type::Str,
             default: json!(["rust", "python"]),
-            choices: vec![
-                "rust".to_string(),
-                "python".to_string(),
-                "go".to_string(),
-                "java".to_string(),
-            ],
+            choices: choices(&["rust", "python", "go", "java"]),
             multiselect: true,
             secret: None,
             ask_if: String::new(),
             schema: None,
             schema_file: None,
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
             validation: create_test_validation(),
         }
     }
@@ -402,6 +437,13 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: None,
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
             validation: create_test_validation(),
         }
     }
@@ -417,6 +459,13 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: None,
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
             validation: create_test_validation(),
         }
     }
@@ -432,6 +481,13 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: None,
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
             validation: create_test_validation(),
         }
     }
@@ -455,6 +511,41 @@ mod tests {
         assert!(calls[0].secret.is_none());
     }
 
+    #[test]
+    fn test_prompt_path_completes_paths() {
+        let mock = MockProvider::new().with_text_response("src/main.rs".to_string());
+        let prompt_handler = PromptHandler::new(mock);
+
+        let mut question = create_text_question();
+        question.r#type = Type::Path;
+        let context = PromptContext::new(&question, &Value::Null, "Entry point");
+
+        let result = prompt_handler.create_prompt(&context).unwrap();
+        assert_eq!(result, json!("src/main.rs"));
+
+        let calls = prompt_handler.provider.get_text_calls();
+        assert!(calls[0].complete_paths);
+    }
+
+    #[test]
+    fn test_prompt_date_shows_format() {
+        let mock = MockProvider::new().with_text_response("2024-01-31".to_string());
+        let prompt_handler = PromptHandler::new(mock);
+
+        let mut question = create_text_question();
+        question.r#type = Type::Date;
+        let default_value = json!("2024-01-01");
+        let context = PromptContext::new(&question, &default_value, "Release date");
+
+        let result = prompt_handler.create_prompt(&context).unwrap();
+        assert_eq!(result, json!("2024-01-31"));
+
+        let calls = prompt_handler.provider.get_text_calls();
+        assert_eq!(calls[0].prompt, "Release date (%Y-%m-%d)");
+        assert_eq!(calls[0].default, Some("2024-01-01".to_string()));
+        assert!(!calls[0].complete_paths);
+    }
+
     #[test]
     fn test_prompt_text_with_secret() {
         let mock = MockProvider::new().with_text_response("secret123".to_string());
@@ -709,7 +800,7 @@ mod tests {
         let mock = MockProvider::new();
         let prompt_handler = PromptHandler::new(mock);
 
-        let choices = vec!["red".to_string(), "blue".to_string(), "green".to_string()];
+        let choices = choices(&["red", "blue", "green"]);
 
         assert_eq!(
             prompt_handler.find_default_choice_index(&choices, &json!("blue")),
@@ -731,25 +822,18 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_string_array() {
+    fn test_extract_array() {
         let mock = MockProvider::new();
         let prompt_handler = PromptHandler::new(mock);
 
         assert_eq!(
-            prompt_handler.extract_string_array(&json!(["a", "b", "c"])),
-            vec!["a", "b", "c"]
-        );
-        assert_eq!(
-            prompt_handler.extract_string_array(&json!(["a", 42, "c"])),
-            vec!["a", "c"] // Should skip non-string values
-        );
-        assert_eq!(
-            prompt_handler.extract_string_array(&Value::Null),
-            Vec::<String>::new()
+            prompt_handler.extract_array(&json!(["a", 42, "c"])),
+            vec![json!("a"), json!(42), json!("c")]
         );
+        assert_eq!(prompt_handler.extract_array(&Value::Null), Vec::<Value>::new());
         assert_eq!(
-            prompt_handler.extract_string_array(&json!("not an array")),
-            Vec::<String>::new()
+            prompt_handler.extract_array(&json!("not an array")),
+            Vec::<Value>::new()
         );
     }
 
@@ -758,13 +842,32 @@ mod tests {
         let mock = MockProvider::new();
         let prompt_handler = PromptHandler::new(mock);
 
-        let choices = vec!["rust".to_string(), "python".to_string(), "go".to_string()];
-        let defaults = vec!["rust".to_string(), "go".to_string()];
+        let choices = choices(&["rust", "python", "go"]);
+        let defaults = vec![json!("rust"), json!("go")];
 
         let result = prompt_handler.create_choice_defaults(&choices, &defaults);
         assert_eq!(result, vec![true, false, true]);
     }
 
+    #[test]
+    fn test_prompt_choices_show_labels_and_return_values() {
+        let mock = MockProvider::new().with_single_choice_response(1);
+        let prompt_handler = PromptHandler::new(mock);
+
+        let mut question = create_single_choice_question();
+        question.choices =
+            serde_yaml::from_str("[8080, {value: 443, label: HTTPS}]").unwrap();
+        let default_value = json!(443);
+        let context = PromptContext::new(&question, &default_value, "Port");
+
+        let result = prompt_handler.create_prompt(&context).unwrap();
+        assert_eq!(result, json!(443));
+
+        let calls = prompt_handler.provider.get_single_choice_calls();
+        assert_eq!(calls[0].choices, vec!["8080", "HTTPS"]);
+        assert_eq!(calls[0].default_index, Some(1));
+    }
+
     #[test]
     fn test_secret_config_with_empty_mismatch_error() {
         let mock = MockProvider::new().with_text_response("password".to_string());
@@ -773,7 +876,7 @@ mod tests {
         let mut question = create_secret_question();
         question.secret = Some(Secret {
             confirm: true,
-            mistmatch_err: String::new(), // Empty error message
+            mismatch_error: String::new(), // Empty error message
         });
 
         let context = PromptContext::new(&question, &Value::Null, "Enter password");
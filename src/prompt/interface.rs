@@ -12,6 +12,8 @@ pub struct TextPromptConfig {
     pub prompt: String,
     pub default: Option<String>,
     pub secret: Option<SecretConfig>,
+    /// Whether filesystem paths are completed with the Tab key
+    pub complete_paths: bool,
 }
 
 /// Configuration for password/secret input
@@ -36,7 +36,14 @@ pub fn get_prompt_provider() -> impl PromptProvider {
 /// #     ask_if: String::new(),
 /// #     schema: None,
 /// #     schema_file: None,
-/// #     validation: baker::config::types::get_default_validation(),
+/// #     transform: None,
+/// #     required: false,
+/// #     allow_empty: true,
+/// #     env: None,
+/// #     secret_command: None,
+/// #     path: None,
+/// #     format: None,
+/// #     validation: vec![baker::config::types::get_default_validation()],
 /// # };
 /// # let default = json!("demo");
 /// let answer = ask_question(&question, &default, "Project name".to_string())?;
@@ -82,7 +89,14 @@ fn confirm_with_provider<P: PromptProvider>(
         ask_if: String::new(),
         schema: None,
         schema_file: None,
-        validation: get_default_validation(),
+        transform: None,
+        required: false,
+        allow_empty: true,
+        env: None,
+        secret_command: None,
+        path: None,
+        format: None,
+        validation: vec![get_default_validation()],
     };
 
     let default_value = Value::Bool(false);
@@ -155,6 +169,7 @@ mod tests {
                         prompt: context.help.to_string(),
                         default: Some(context.default.to_string()),
                         secret: None,
+                        complete_paths: false,
                     };
                     self.prompt_text(&config).map(Value::String)
                 }
@@ -177,7 +192,14 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         };
         let answer = super::ask_question_with_provider(
             &question,
@@ -220,7 +242,14 @@ mod tests {
             ask_if: String::new(),
             schema: None,
             schema_file: None,
-            validation: get_default_validation(),
+            transform: None,
+            required: false,
+            allow_empty: true,
+            env: None,
+            secret_command: None,
+            path: None,
+            format: None,
+            validation: vec![get_default_validation()],
         };
         let context = PromptContext::new(&question, &Value::Bool(false), "Help");
         let result = provider.prompt(&context);
@@ -234,6 +263,7 @@ mod tests {
             prompt: "Enter text".to_string(),
             default: Some("default".to_string()),
             secret: None,
+            complete_paths: false,
         };
         let result = TextPrompter::prompt_text(&provider, &config);
         assert_eq!(result.unwrap(), "test");
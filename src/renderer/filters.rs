@@ -1,5 +1,14 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::Local;
 use log::warn;
+use minijinja::value::{Object, Value};
+use minijinja::{Error, ErrorKind, State};
 use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
 
 // Re-export all the case conversion and string manipulation functions
 pub use cruet::{
@@ -32,6 +41,249 @@ pub fn regex_filter(val: &str, re: &str) -> bool {
     }
 }
 
+/// Seed shared by the random functions of an environment, see [`RenderRandom`].
+pub type RandomSeed = Arc<RwLock<u64>>;
+
+/// Returns a new random seed.
+pub fn random_seed() -> u64 {
+    RandomState::new().hash_one(std::time::SystemTime::now())
+}
+
+/// Random number generator of a single render (SplitMix64).
+///
+/// Every render starts from the environment's seed mixed with the template name, so
+/// a seeded run produces the same values for a file however many files are rendered
+/// and in which order. Not suitable for secrets.
+#[derive(Debug)]
+struct RenderRandom(Mutex<u64>);
+
+impl Object for RenderRandom {}
+
+impl RenderRandom {
+    /// Returns the generator of the render `state` belongs to.
+    fn of(state: &State, seed: &RandomSeed) -> Arc<Self> {
+        state.get_or_set_temp_object("baker_random", || {
+            // SHA-256 rather than `DefaultHasher`, whose output may change between
+            // Rust releases, so that a stored seed keeps producing the same values
+            let digest = Sha256::digest(state.name().as_bytes());
+            let name_hash = u64::from_le_bytes(
+                digest[..8].try_into().expect("SHA-256 digests are 32 bytes"),
+            );
+            let seed = *seed.read().unwrap_or_else(PoisonError::into_inner);
+            RenderRandom(Mutex::new(seed ^ name_hash))
+        })
+    }
+
+    fn next_u64(&self) -> u64 {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// `uuid()`: a random (version 4) UUID.
+pub fn uuid(state: &State, seed: &RandomSeed) -> String {
+    let random = RenderRandom::of(state, seed);
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&random.next_u64().to_le_bytes());
+    bytes[8..].copy_from_slice(&random.next_u64().to_le_bytes());
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// `random_string(length, chars)`: `length` characters picked from `chars`, which
+/// defaults to ASCII letters and digits.
+pub fn random_string(
+    state: &State,
+    seed: &RandomSeed,
+    length: usize,
+    chars: Option<&str>,
+) -> Result<String, Error> {
+    let chars: Vec<char> = chars
+        .unwrap_or("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789")
+        .chars()
+        .collect();
+    if chars.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            "random_string needs at least one character to pick from",
+        ));
+    }
+    let random = RenderRandom::of(state, seed);
+    Ok((0..length)
+        .map(|_| chars[(random.next_u64() % chars.len() as u64) as usize])
+        .collect())
+}
+
+/// `now(format)`: the current local time, formatted with a `strftime` format string
+/// (RFC 3339 by default).
+pub fn now(format: Option<&str>) -> Result<String, Error> {
+    let now = Local::now();
+    let Some(format) = format else {
+        return Ok(now.to_rfc3339());
+    };
+    let mut formatted = String::new();
+    std::fmt::Write::write_fmt(&mut formatted, format_args!("{}", now.format(format)))
+        .map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidOperation,
+                format!("invalid date format '{format}'"),
+            )
+        })?;
+    Ok(formatted)
+}
+
+/// `env(name, default)`: the environment variable `name`, or `default` (none when not
+/// given) if it is not set.
+pub fn env(name: &str, default: Option<Value>) -> Value {
+    match std::env::var(name) {
+        Ok(value) => Value::from(value),
+        Err(_) => default.unwrap_or(Value::from(())),
+    }
+}
+
+/// `sha256`: the hex-encoded SHA-256 digest of a string.
+pub fn sha256(value: &str) -> String {
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
+
+/// `base64_encode`: a string encoded as standard base64.
+pub fn base64_encode(value: &str) -> String {
+    BASE64.encode(value)
+}
+
+/// `base64_decode`: decodes standard base64 into a UTF-8 string.
+pub fn base64_decode(value: &str) -> Result<String, Error> {
+    let bytes = BASE64.decode(value.trim()).map_err(|e| {
+        Error::new(ErrorKind::InvalidOperation, "invalid base64").with_source(e)
+    })?;
+    String::from_utf8(bytes).map_err(|e| {
+        Error::new(ErrorKind::InvalidOperation, "base64 value is not UTF-8")
+            .with_source(e)
+    })
+}
+
+/// `to_json(indent)`: a value serialized as JSON, pretty-printed with `indent` spaces
+/// (2 by default).
+pub fn to_json(value: Value, indent: Option<usize>) -> Result<String, Error> {
+    let indent = " ".repeat(indent.unwrap_or(2));
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    serde::Serialize::serialize(&value, &mut serializer).map_err(serialize_error)?;
+    String::from_utf8(out).map_err(serialize_error)
+}
+
+/// `to_yaml`: a value serialized as YAML, without the trailing newline.
+pub fn to_yaml(value: Value) -> Result<String, Error> {
+    let yaml = serde_yaml::to_string(&value).map_err(serialize_error)?;
+    Ok(yaml.trim_end_matches('\n').to_string())
+}
+
+/// `to_toml`: a map serialized as a TOML document, without the trailing newline.
+pub fn to_toml(value: Value) -> Result<String, Error> {
+    let toml = toml::to_string(&value).map_err(serialize_error)?;
+    Ok(toml.trim_end_matches('\n').to_string())
+}
+
+fn serialize_error(e: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::new(ErrorKind::InvalidOperation, "cannot serialize value").with_source(e)
+}
+
+/// `slugify(separator)`: lowercases a string and joins its runs of letters and
+/// digits with `separator` (`-` by default), e.g. `Hello, World!` -> `hello-world`.
+pub fn slugify(value: &str, separator: Option<&str>) -> String {
+    value
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator.unwrap_or("-"))
+}
+
+/// `regex_replace(pattern, replacement)`: replaces every match of `pattern`.
+/// The replacement can refer to groups as `$1` or `${name}`.
+pub fn regex_replace(
+    value: &str,
+    pattern: &str,
+    replacement: &str,
+) -> Result<String, Error> {
+    Ok(compile_regex(pattern)?.replace_all(value, replacement).into_owned())
+}
+
+/// `regex_capture(pattern, group)`: the text of `group` (a number or a name) in the
+/// first match of `pattern`, or none. Defaults to the first group, or the whole
+/// match when the pattern has no groups.
+pub fn regex_capture(
+    value: &str,
+    pattern: &str,
+    group: Option<Value>,
+) -> Result<Value, Error> {
+    let re = compile_regex(pattern)?;
+    let Some(captures) = re.captures(value) else {
+        return Ok(Value::from(()));
+    };
+    let capture = match &group {
+        Some(group) => match group.as_str() {
+            Some(name) => captures.name(name),
+            None => captures.get(usize::try_from(group.clone())?),
+        },
+        None => captures.get(if re.captures_len() > 1 { 1 } else { 0 }),
+    };
+    Ok(capture.map(|capture| Value::from(capture.as_str())).unwrap_or(Value::from(())))
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex, Error> {
+    Regex::new(pattern).map_err(|e| {
+        Error::new(ErrorKind::InvalidOperation, format!("invalid regex '{pattern}'"))
+            .with_source(e)
+    })
+}
+
+/// `semver_cmp(other)`: compares two semantic versions by precedence, returning -1, 0
+/// or 1. Build metadata is ignored.
+pub fn semver_cmp(version: &str, other: &str) -> Result<i32, Error> {
+    Ok(match parse_version(version)?.cmp_precedence(&parse_version(other)?) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    })
+}
+
+/// `semver_matches(requirement)`: whether a semantic version meets a Cargo-style
+/// requirement, e.g. `^1.2`, `~1.4` or `>=1.2, <2`.
+pub fn semver_matches(version: &str, requirement: &str) -> Result<bool, Error> {
+    let requirement = semver::VersionReq::parse(requirement.trim()).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("invalid version requirement '{requirement}'"),
+        )
+        .with_source(e)
+    })?;
+    Ok(requirement.matches(&parse_version(version)?))
+}
+
+/// Parses a semantic version, allowing a leading `v` as in git tags.
+fn parse_version(version: &str) -> Result<semver::Version, Error> {
+    let trimmed = version.trim();
+    semver::Version::parse(trimmed.strip_prefix('v').unwrap_or(trimmed)).map_err(|e| {
+        Error::new(ErrorKind::InvalidOperation, format!("invalid version '{version}'"))
+            .with_source(e)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,4 +302,90 @@ mod tests {
     fn test_regex_filter_invalid_regex() {
         assert!(!regex_filter("anything", r"([unclosed"));
     }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!", None), "hello-world");
+        assert_eq!(slugify("  My  Project 2 ", Some("_")), "my_project_2");
+    }
+
+    #[test]
+    fn test_hash_and_base64() {
+        assert_eq!(
+            sha256(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(base64_encode("baker"), "YmFrZXI=");
+        assert_eq!(base64_decode("YmFrZXI=").unwrap(), "baker");
+        assert!(base64_decode("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_serialization_filters() {
+        let value =
+            Value::from_serialize(serde_json::json!({"name": "baker", "tags": ["a"]}));
+        assert_eq!(
+            to_json(value.clone(), None).unwrap(),
+            "{\n  \"name\": \"baker\",\n  \"tags\": [\n    \"a\"\n  ]\n}"
+        );
+        assert_eq!(to_yaml(value.clone()).unwrap(), "name: baker\ntags:\n- a");
+        assert_eq!(to_toml(value).unwrap(), "name = \"baker\"\ntags = [\"a\"]");
+        assert!(to_toml(Value::from(1)).is_err());
+    }
+
+    #[test]
+    fn test_regex_replace_and_capture() {
+        assert_eq!(regex_replace("a1b22", r"\d+", "#").unwrap(), "a#b#");
+        assert_eq!(
+            regex_replace("john smith", r"(\w+) (\w+)", "$2 $1").unwrap(),
+            "smith john"
+        );
+        assert_eq!(
+            regex_capture("v1.2.3", r"v(\d+)\.(\d+)", None).unwrap(),
+            Value::from("1")
+        );
+        assert_eq!(
+            regex_capture("v1.2.3", r"v(\d+)\.(\d+)", Some(Value::from(2))).unwrap(),
+            Value::from("2")
+        );
+        assert_eq!(
+            regex_capture("v1.2", r"v(?P<major>\d+)", Some(Value::from("major")))
+                .unwrap(),
+            Value::from("1")
+        );
+        assert!(regex_capture("abc", r"\d", None).unwrap().is_none());
+        assert!(regex_replace("abc", r"([unclosed", "").is_err());
+    }
+
+    #[test]
+    fn test_semver() {
+        assert_eq!(semver_cmp("1.2.3", "1.10.0").unwrap(), -1);
+        assert_eq!(semver_cmp("v2.0.0", "2.0.0+build").unwrap(), 0);
+        assert_eq!(semver_cmp("1.0.0", "1.0.0-rc.1").unwrap(), 1);
+        assert_eq!(semver_cmp("1.0.0-alpha.2", "1.0.0-alpha.10").unwrap(), -1);
+        assert_eq!(semver_cmp("1.0.0-alpha", "1.0.0-alpha.1").unwrap(), -1);
+        assert!(semver_matches("1.4.2", ">=1.2, <2").unwrap());
+        assert!(!semver_matches("2.0.0", ">=1.2, <2").unwrap());
+        assert!(semver_matches("1.4.2", "^1.2").unwrap());
+        assert!(!semver_matches("2.0.0", "^1.2").unwrap());
+        assert!(semver_matches("0.2.5", "^0.2.3").unwrap());
+        assert!(!semver_matches("0.3.0", "^0.2.3").unwrap());
+        assert!(semver_matches("1.4.9", "~1.4").unwrap());
+        assert!(!semver_matches("1.5.0", "~1.4").unwrap());
+        assert!(semver_matches("1.4.2", "1.4").unwrap());
+        assert!(!semver_matches("1.5.0-rc.1", ">=1.2").unwrap());
+        assert!(semver_cmp("one", "1.0.0").is_err());
+        assert!(semver_matches("1.0.0", "=>1.0").is_err());
+    }
+
+    #[test]
+    fn test_now_and_env() {
+        assert_eq!(now(Some("%Y")).unwrap().len(), 4);
+        assert!(now(Some("%Q")).is_err());
+        assert_eq!(
+            env("BAKER_TEST_SURELY_UNSET_VARIABLE", Some(Value::from("fallback"))),
+            Value::from("fallback")
+        );
+        assert!(env("BAKER_TEST_SURELY_UNSET_VARIABLE", None).is_none());
+    }
 }
@@ -0,0 +1,204 @@
+//! Handlebars-based template renderer, for templates written for Handlebars.
+
+use super::{template_files, Delimiters, SharedContext};
+use crate::error::{Error, Result};
+use crate::ext::PathExt;
+use crate::renderer::interface::TemplateRenderer;
+use globset::GlobSet;
+use handlebars::{
+    handlebars_helper, no_escape, Context, Handlebars, RenderContext, Renderable,
+    StringOutput,
+};
+use std::path::Path;
+
+/// Helper used to evaluate expressions, see [`TemplateRenderer::evaluate_expression`].
+const JSON_HELPER: &str = "baker_json";
+
+handlebars_helper!(baker_json: |value: Json| value.to_string());
+
+/// Handlebars-based template rendering engine.
+///
+/// Expressions such as `ask_if` are Handlebars parameters, e.g. `use_docker` or
+/// `(eq language "Python")`.
+pub struct HandlebarsRenderer {
+    registry: Handlebars<'static>,
+    context: SharedContext,
+}
+
+impl HandlebarsRenderer {
+    /// Creates a new HandlebarsRenderer instance. Output is not HTML-escaped.
+    pub fn new() -> Self {
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(no_escape);
+        registry.register_helper(JSON_HELPER, Box::new(baker_json));
+        Self { registry, context: SharedContext::default() }
+    }
+}
+
+impl Default for HandlebarsRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemplateRenderer for HandlebarsRenderer {
+    fn add_template(&mut self, name: &str, template: &str) -> Result<()> {
+        Ok(self.registry.register_template_string(&name.replace('\\', "/"), template)?)
+    }
+
+    fn add_prelude(&mut self, _name: &str, _template: &str) -> Result<()> {
+        Err(Error::UnsupportedByEngine {
+            engine: "handlebars".to_string(),
+            feature: "prelude templates".to_string(),
+        })
+    }
+
+    fn add_global(&mut self, name: &str, value: serde_json::Value) {
+        self.context.globals.insert(name.to_string(), value);
+    }
+
+    /// Registers every matching file right away, as Handlebars resolves partials
+    /// when a template is rendered.
+    fn add_template_dir(&mut self, import_root: &Path, globs: GlobSet) {
+        for (name, template) in template_files(import_root, &globs) {
+            if let Err(e) = self.registry.register_template_string(&name, template) {
+                log::warn!("Cannot load template '{name}': {e}");
+            }
+        }
+    }
+
+    fn set_delimiters(&mut self, delimiters: &Delimiters) -> Result<()> {
+        if *delimiters == Delimiters::default() {
+            return Ok(());
+        }
+        Err(Error::UnsupportedByEngine {
+            engine: "handlebars".to_string(),
+            feature: "custom delimiters".to_string(),
+        })
+    }
+
+    fn set_context_alias(&mut self, name: &str) {
+        self.context.alias = Some(name.to_string());
+    }
+
+    fn set_strict_undefined(&mut self, strict: bool) {
+        self.registry.set_strict_mode(strict);
+    }
+
+    /// Handlebars templates have no random functions, so the seed is unused.
+    fn set_random_seed(&mut self, _seed: u64) {}
+
+    fn render(
+        &self,
+        template: &str,
+        context: &serde_json::Value,
+        template_name: Option<&str>,
+    ) -> Result<String> {
+        // Compiling the template under its name makes errors point at the file.
+        let name = template_name.map(str::to_string);
+        let mut compiled =
+            handlebars::Template::compile(template).map_err(|e| match &name {
+                Some(name) => e.in_template(name.clone()),
+                None => e,
+            })?;
+        compiled.name = name;
+        let context = Context::wraps(self.context.merge(context))?;
+        let mut render_context = RenderContext::new(compiled.name.as_ref());
+        let mut output = StringOutput::new();
+        compiled.render(&self.registry, &context, &mut render_context, &mut output)?;
+        Ok(output.into_string().map_err(handlebars::RenderError::from)?)
+    }
+
+    fn render_path(
+        &self,
+        template_path: &Path,
+        context: &serde_json::Value,
+    ) -> Result<String> {
+        let path_str = template_path.to_str_checked()?;
+        self.render(path_str, context, None).map_err(|e| Error::ProcessError {
+            source_path: path_str.to_string(),
+            e: e.to_string(),
+        })
+    }
+
+    fn execute_expression(
+        &self,
+        expr_str: &str,
+        context: &serde_json::Value,
+    ) -> Result<bool> {
+        if expr_str.is_empty() {
+            return Ok(true);
+        }
+        let template = format!("{{{{#if {expr_str}}}}}true{{{{/if}}}}");
+        Ok(self.render(&template, context, None)? == "true")
+    }
+
+    fn evaluate_expression(
+        &self,
+        expr_str: &str,
+        context: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let template = format!("{{{{{JSON_HELPER} {expr_str}}}}}");
+        Ok(serde_json::from_str(&self.render(&template, context, None)?)?)
+    }
+
+    fn check_template(&self, template: &str, _template_name: Option<&str>) -> Result<()> {
+        handlebars::Template::compile(template)?;
+        Ok(())
+    }
+
+    fn check_expression(&self, expr_str: &str) -> Result<()> {
+        if !expr_str.is_empty() {
+            handlebars::Template::compile(&format!("{{{{#if {expr_str}}}}}{{{{/if}}}}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_templates_paths_and_expressions() {
+        let mut renderer = HandlebarsRenderer::new();
+        renderer.add_template("header", "# {{project_name}}").unwrap();
+        renderer.add_global("license", json!("MIT"));
+        let context = json!({ "project_name": "<baker>", "language": "Rust" });
+
+        let rendered = renderer
+            .render("{{> header}} ({{#if license}}{{license}}{{/if}})", &context, None)
+            .unwrap();
+        assert_eq!(rendered, "# <baker> (MIT)");
+        let path = renderer.render_path(Path::new("src/{{language}}.txt"), &context);
+        assert_eq!(path.unwrap(), "src/Rust.txt");
+        assert!(renderer.execute_expression("(eq language \"Rust\")", &context).unwrap());
+        assert!(!renderer.execute_expression("missing", &context).unwrap());
+        assert_eq!(
+            renderer.evaluate_expression("project_name", &context).unwrap(),
+            json!("<baker>")
+        );
+        assert!(renderer.check_template("{{#if x}}", None).is_err());
+        assert!(renderer.add_prelude("macros.hbs", "").is_err());
+    }
+
+    #[test]
+    fn strict_mode_rejects_unknown_variables() {
+        let mut renderer = HandlebarsRenderer::new();
+        assert_eq!(renderer.render("{{missing}}", &json!({}), None).unwrap(), "");
+        renderer.set_strict_undefined(true);
+        assert!(renderer.render("{{missing}}", &json!({}), None).is_err());
+    }
+
+    #[test]
+    fn errors_name_the_template() {
+        let mut renderer = HandlebarsRenderer::new();
+        let err = renderer.render("{{#if x}}", &json!({}), Some("README.md.hbs"));
+        assert!(err.unwrap_err().to_string().contains("README.md.hbs"));
+
+        renderer.set_strict_undefined(true);
+        let err = renderer.render("\n{{missing}}", &json!({}), Some("README.md.hbs"));
+        assert!(err.unwrap_err().to_string().contains("\"README.md.hbs\" line 2"));
+    }
+}
@@ -1,8 +1,12 @@
 use crate::error::Result;
+use crate::renderer::Delimiters;
+use globset::GlobSet;
 use std::path::Path;
 
 /// Trait for template rendering engines.
-pub trait TemplateRenderer {
+///
+/// Renderers are shared by the threads that render template files in parallel.
+pub trait TemplateRenderer: Send + Sync {
     /// Adds a template to the renderer's template collection.
     ///
     /// # Arguments
@@ -10,12 +14,73 @@ pub trait TemplateRenderer {
     /// * `template` - Template content as string
     ///
     /// # Returns
-    /// * `Result<(), minijinja::Error>` - Success or MiniJinja error
-    fn add_template(
-        &mut self,
-        name: &str,
-        template: &str,
-    ) -> Result<(), minijinja::Error>;
+    /// * `Result<()>` - Success or the compilation error
+    fn add_template(&mut self, name: &str, template: &str) -> Result<()>;
+
+    /// Adds a template whose top-level macros and variables are imported into every
+    /// template and path rendered afterwards, as if they started with
+    /// `{% from name import ... %}`.
+    ///
+    /// # Arguments
+    /// * `name` - Name to identify the template
+    /// * `template` - Template content as string
+    ///
+    /// Engines without macros return [`Error::UnsupportedByEngine`].
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or the compilation error
+    ///
+    /// [`Error::UnsupportedByEngine`]: crate::error::Error::UnsupportedByEngine
+    fn add_prelude(&mut self, name: &str, template: &str) -> Result<()>;
+
+    /// Exposes `value` as the global variable `name` in templates, paths and
+    /// expressions. Render contexts take precedence over globals.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the variable
+    /// * `value` - Value of the variable
+    fn add_global(&mut self, name: &str, value: serde_json::Value);
+
+    /// Makes the files below `import_root` that match `globs` available to
+    /// `import`, `include` and `extends`, named by their path relative to
+    /// `import_root`. Directories added later take precedence over earlier ones.
+    ///
+    /// Whether the files are loaded right away or when first used depends on the
+    /// engine. Engines that load them right away only log a warning for files that
+    /// fail to compile, which are then missing when imported.
+    ///
+    /// # Arguments
+    /// * `import_root` - Directory the template names are resolved against
+    /// * `globs` - Patterns (anchored at `import_root`) of the files that can be loaded
+    fn add_template_dir(&mut self, import_root: &Path, globs: GlobSet);
+
+    /// Also exposes the whole render context under `name`, so that templates written
+    /// for cookiecutter can refer to the answer `x` as `cookiecutter.x`.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the variable holding the context
+    fn set_context_alias(&mut self, name: &str);
+
+    /// Makes undefined variables an error instead of rendering them as empty
+    /// strings, in both templates and paths.
+    ///
+    /// # Arguments
+    /// * `strict` - Whether undefined variables are an error
+    fn set_strict_undefined(&mut self, strict: bool);
+
+    /// Seeds the values of `uuid()` and `random_string()`, so that rendering a
+    /// template again with the same seed produces the same output.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the random functions
+    fn set_random_seed(&mut self, seed: u64);
+
+    /// Replaces the `{% %}`, `{{ }}` and `{# #}` delimiters of templates and paths.
+    /// Engines that cannot change them fail unless `delimiters` are the defaults.
+    ///
+    /// # Arguments
+    /// * `delimiters` - The delimiters templates are written with
+    fn set_delimiters(&mut self, delimiters: &Delimiters) -> Result<()>;
 
     /// Renders a template string with the given context.
     ///
@@ -58,4 +123,37 @@ pub trait TemplateRenderer {
     /// * `Result<bool>` - Whether the expression evaluates to true
     fn execute_expression(&self, expr: &str, context: &serde_json::Value)
         -> Result<bool>;
+
+    /// Evaluates a template expression and returns its value.
+    ///
+    /// # Arguments
+    /// * `expr` - Expression to evaluate
+    /// * `context` - Context variables for evaluation
+    ///
+    /// # Returns
+    /// * `Result<serde_json::Value>` - The value the expression evaluates to
+    fn evaluate_expression(
+        &self,
+        expr: &str,
+        context: &serde_json::Value,
+    ) -> Result<serde_json::Value>;
+
+    /// Compiles a template without rendering it, reporting syntax errors.
+    ///
+    /// # Arguments
+    /// * `template` - Template string to compile
+    /// * `template_name` - Optional name for the template (used in error messages)
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or the compilation error
+    fn check_template(&self, template: &str, template_name: Option<&str>) -> Result<()>;
+
+    /// Compiles an expression without evaluating it, reporting syntax errors.
+    ///
+    /// # Arguments
+    /// * `expr` - Expression to compile
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or the compilation error
+    fn check_expression(&self, expr: &str) -> Result<()>;
 }
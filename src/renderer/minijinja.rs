@@ -1,21 +1,53 @@
 use super::filters::*;
-use crate::{error::Result, ext::PathExt, renderer::interface::TemplateRenderer};
-use minijinja::{AutoEscape, Environment};
+use crate::{
+    error::Result,
+    ext::PathExt,
+    renderer::{interface::TemplateRenderer, Delimiters},
+};
+use globset::GlobSet;
+use minijinja::syntax::SyntaxConfig;
+use minijinja::{AutoEscape, Environment, ErrorKind, State, UndefinedBehavior};
 use serde_json::json;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// A directory whose files matching `globs` can be imported and included.
+struct TemplateDir {
+    root: PathBuf,
+    globs: GlobSet,
+}
+
+/// Template directories shared by the loaders of both environments.
+type TemplateDirs = Arc<RwLock<Vec<TemplateDir>>>;
 
 /// MiniJinja-based template rendering engine.
 pub struct MiniJinjaRenderer {
     /// MiniJinja environment instance
     env: Environment<'static>,
+    /// The same environment without auto-escaping, used to render paths
+    path_env: Environment<'static>,
+    /// Directories searched when a template imports or includes another one
+    template_dirs: TemplateDirs,
+    /// Seed of `uuid()` and `random_string()`, see [`TemplateRenderer::set_random_seed`]
+    random_seed: RandomSeed,
+    /// `from ... import ...` statements of the prelude templates, see [`Self::render_internal`]
+    prelude_imports: Vec<String>,
+    /// Delimiters the templates are written with
+    delimiters: Delimiters,
     /// Default context that will be merged with any provided context
     default_context: serde_json::Value,
+    /// Variable that also holds the whole context, see [`TemplateRenderer::set_context_alias`]
+    context_alias: Option<String>,
 }
 
 impl MiniJinjaRenderer {
     /// Creates a new MiniJinjaRenderer instance with default environment.
     pub fn new() -> Self {
-        let mut env = Environment::new();
+        let template_dirs = TemplateDirs::default();
+        let random_seed = RandomSeed::new(RwLock::new(random_seed()));
+        let env = Self::environment(&template_dirs, &random_seed);
+        let mut path_env = Self::environment(&template_dirs, &random_seed);
+        path_env.set_auto_escape_callback(|_| AutoEscape::None);
         let default_context = json!({
             "platform": {
                 "os": std::env::consts::OS,
@@ -24,6 +56,26 @@ impl MiniJinjaRenderer {
             }
         });
 
+        Self {
+            env,
+            path_env,
+            template_dirs,
+            random_seed,
+            prelude_imports: Vec::new(),
+            delimiters: Delimiters::default(),
+            default_context,
+            context_alias: None,
+        }
+    }
+
+    /// Builds an environment with Baker's filters that loads imported and included
+    /// templates from `template_dirs` when they are first used.
+    fn environment(
+        template_dirs: &TemplateDirs,
+        random_seed: &RandomSeed,
+    ) -> Environment<'static> {
+        let mut env = Environment::new();
+
         // Add all the custom filters
         env.add_filter("camel_case", to_camel_case);
         env.add_filter("kebab_case", to_kebab_case);
@@ -36,24 +88,69 @@ impl MiniJinjaRenderer {
         env.add_filter("singular", to_singular);
         env.add_filter("foreign_key", to_foreign_key);
         env.add_filter("regex", regex_filter);
+        env.add_filter("regex_replace", regex_replace);
+        env.add_filter("regex_capture", regex_capture);
+        env.add_filter("sha256", sha256);
+        env.add_filter("base64_encode", base64_encode);
+        env.add_filter("base64_decode", base64_decode);
+        env.add_filter("to_json", to_json);
+        env.add_filter("to_yaml", to_yaml);
+        env.add_filter("to_toml", to_toml);
+        env.add_filter("slugify", slugify);
+        env.add_filter("semver_cmp", semver_cmp);
+        env.add_filter("semver_matches", semver_matches);
+
+        // Add the custom functions
+        env.add_function("now", now);
+        env.add_function("env", self::env);
+        let seed = Arc::clone(random_seed);
+        env.add_function("uuid", move |state: &State| uuid(state, &seed));
+        let seed = Arc::clone(random_seed);
+        env.add_function(
+            "random_string",
+            move |state: &State, length: usize, chars: Option<&str>| {
+                random_string(state, &seed, length, chars)
+            },
+        );
+
+        // Python string and dict methods such as `.lower()`, used by cookiecutter templates
+        env.set_unknown_method_callback(
+            minijinja_contrib::pycompat::unknown_method_callback,
+        );
+
+        let template_dirs = Arc::clone(template_dirs);
+        env.set_loader(move |name| load_template(&template_dirs, name));
+        env
+    }
 
-        Self { env, default_context }
+    /// Returns `context` with itself added under the context alias, if one is set.
+    fn aliased_context(&self, context: &serde_json::Value) -> serde_json::Value {
+        let mut context = context.clone();
+        if let (Some(alias), Some(object)) = (&self.context_alias, context.as_object()) {
+            let aliased = serde_json::Value::Object(object.clone());
+            context[alias.as_str()] = aliased;
+        }
+        context
     }
 
     /// Internal helper to render templates with context merging
     fn render_internal(
         &self,
+        env: &Environment<'_>,
         template: &str,
         context: &serde_json::Value,
         template_name: Option<&str>,
-        auto_escape_override: Option<AutoEscape>,
     ) -> Result<String> {
-        let mut env = self.env.clone();
-        if let Some(auto_escape) = auto_escape_override {
-            env.set_auto_escape_callback(move |_| auto_escape);
-        }
         let name = template_name.unwrap_or("temp");
-        env.add_template(name, template)?;
+        // The imports have no line break, so error line numbers stay the same
+        let imports: String = self
+            .prelude_imports
+            .iter()
+            .map(|import| self.delimiters.block(import))
+            .collect();
+        let template = format!("{imports}{template}");
+        let tmpl = env.template_from_named_str(name, &template)?;
+        let context = &self.aliased_context(context);
 
         // Merge the default context with the provided context
         let merged_context = if let (Some(default_obj), Some(context_obj)) =
@@ -69,11 +166,38 @@ impl MiniJinjaRenderer {
             context.clone()
         };
 
-        let tmpl = env.get_template(name)?;
         Ok(tmpl.render(merged_context)?)
     }
 }
 
+/// Reads the template `name` from the most recently added directory that has a
+/// matching file. Like MiniJinja's path loader, names leaving the directory are
+/// not resolved.
+fn load_template(
+    template_dirs: &RwLock<Vec<TemplateDir>>,
+    name: &str,
+) -> Result<Option<String>, minijinja::Error> {
+    if Path::new(name).is_absolute() || name.split(['/', '\\']).any(|part| part == "..") {
+        return Ok(None);
+    }
+    let template_dirs = template_dirs.read().unwrap_or_else(PoisonError::into_inner);
+    for dir in template_dirs.iter().rev() {
+        let path = dir.root.join(name);
+        if !path.is_file() || !dir.globs.is_match(&path) {
+            continue;
+        }
+        log::debug!("Loading template '{name}' from {}", path.display());
+        return std::fs::read_to_string(&path).map(Some).map_err(|e| {
+            minijinja::Error::new(
+                ErrorKind::InvalidOperation,
+                format!("cannot read template '{name}' from {}", path.display()),
+            )
+            .with_source(e)
+        });
+    }
+    Ok(None)
+}
+
 impl Default for MiniJinjaRenderer {
     fn default() -> Self {
         Self::new()
@@ -81,14 +205,71 @@ impl Default for MiniJinjaRenderer {
 }
 
 impl TemplateRenderer for MiniJinjaRenderer {
-    fn add_template(
-        &mut self,
-        name: &str,
-        template: &str,
-    ) -> Result<(), minijinja::Error> {
+    fn add_template(&mut self, name: &str, template: &str) -> Result<()> {
         // Normalize the template name for cross-platform compatibility
         let normalized_name = name.replace("\\", "/");
-        self.env.add_template_owned(normalized_name, template.to_string())
+        self.path_env
+            .add_template_owned(normalized_name.clone(), template.to_string())?;
+        Ok(self.env.add_template_owned(normalized_name, template.to_string())?)
+    }
+
+    fn add_prelude(&mut self, name: &str, template: &str) -> Result<()> {
+        self.add_template(name, template)?;
+        let name = name.replace("\\", "/");
+        let captured = self.env.get_template(&name)?.render_captured(())?;
+        let exports = captured.state().exports().join(", ");
+        if !exports.is_empty() {
+            self.prelude_imports.push(format!("from {} import {exports}", json!(name)));
+        }
+        Ok(())
+    }
+
+    fn add_global(&mut self, name: &str, value: serde_json::Value) {
+        let value = minijinja::Value::from_serialize(&value);
+        self.env.add_global(name.to_string(), value.clone());
+        self.path_env.add_global(name.to_string(), value);
+    }
+
+    fn add_template_dir(&mut self, import_root: &Path, globs: GlobSet) {
+        let mut template_dirs =
+            self.template_dirs.write().unwrap_or_else(PoisonError::into_inner);
+        template_dirs.push(TemplateDir { root: import_root.to_path_buf(), globs });
+    }
+
+    fn set_delimiters(&mut self, delimiters: &Delimiters) -> Result<()> {
+        let syntax = SyntaxConfig::builder()
+            .block_delimiters(
+                delimiters.block_start.clone(),
+                delimiters.block_end.clone(),
+            )
+            .variable_delimiters(
+                delimiters.variable_start.clone(),
+                delimiters.variable_end.clone(),
+            )
+            .comment_delimiters(
+                delimiters.comment_start.clone(),
+                delimiters.comment_end.clone(),
+            )
+            .build()?;
+        self.env.set_syntax(syntax.clone());
+        self.path_env.set_syntax(syntax);
+        self.delimiters = delimiters.clone();
+        Ok(())
+    }
+
+    fn set_context_alias(&mut self, name: &str) {
+        self.context_alias = Some(name.to_string());
+    }
+
+    fn set_random_seed(&mut self, seed: u64) {
+        *self.random_seed.write().unwrap_or_else(PoisonError::into_inner) = seed;
+    }
+
+    fn set_strict_undefined(&mut self, strict: bool) {
+        let behavior =
+            if strict { UndefinedBehavior::Strict } else { UndefinedBehavior::Lenient };
+        self.env.set_undefined_behavior(behavior);
+        self.path_env.set_undefined_behavior(behavior);
     }
 
     fn render(
@@ -97,7 +278,7 @@ impl TemplateRenderer for MiniJinjaRenderer {
         context: &serde_json::Value,
         template_name: Option<&str>,
     ) -> Result<String> {
-        self.render_internal(template, context, template_name, None)
+        self.render_internal(&self.env, template, context, template_name)
     }
 
     fn render_path(
@@ -107,11 +288,12 @@ impl TemplateRenderer for MiniJinjaRenderer {
     ) -> Result<String> {
         let path_str = template_path.to_str_checked()?;
         let template_name = template_path.file_name().and_then(|name| name.to_str());
-        self.render_internal(path_str, context, template_name, Some(AutoEscape::None))
-            .map_err(|e| crate::error::Error::ProcessError {
+        self.render_internal(&self.path_env, path_str, context, template_name).map_err(
+            |e| crate::error::Error::ProcessError {
                 source_path: path_str.to_string(),
                 e: e.to_string(),
-            })
+            },
+        )
     }
 
     fn execute_expression(
@@ -124,7 +306,29 @@ impl TemplateRenderer for MiniJinjaRenderer {
             return Ok(true);
         }
         let expr = self.env.compile_expression(expr_str)?;
-        Ok(expr.eval(context)?.is_true())
+        Ok(expr.eval(self.aliased_context(context))?.is_true())
+    }
+
+    fn evaluate_expression(
+        &self,
+        expr_str: &str,
+        context: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let expr = self.env.compile_expression(expr_str)?;
+        let value = expr.eval(self.aliased_context(context))?;
+        Ok(serde_json::to_value(&value)?)
+    }
+
+    fn check_template(&self, template: &str, template_name: Option<&str>) -> Result<()> {
+        self.env.template_from_named_str(template_name.unwrap_or("temp"), template)?;
+        Ok(())
+    }
+
+    fn check_expression(&self, expr_str: &str) -> Result<()> {
+        if !expr_str.is_empty() {
+            self.env.compile_expression(expr_str)?;
+        }
+        Ok(())
     }
 }
 
@@ -179,6 +383,54 @@ mod tests {
         test_template("{{ 'hello' | regex('[') }}", "false");
     }
 
+    #[test]
+    fn test_library_filters_and_functions() {
+        test_template("{{ 'Hello, World!' | slugify }}", "hello-world");
+        test_template("{{ 'baker' | base64_encode | base64_decode }}", "baker");
+        test_template("{{ {'a': [1]} | to_json(0) }}", "{\n\"a\": [\n1\n]\n}");
+        test_template("{{ {'a': 1} | to_yaml }}", "a: 1");
+        test_template("{{ 'v1.2.3' | regex_replace('\\d', 'x') }}", "vx.x.x");
+        test_template("{{ '1.4.0' | semver_matches('>=1.2, <2') }}", "true");
+        test_template("{{ '1.4.0' | semver_cmp('1.10.0') }}", "-1");
+        test_template("{{ env('BAKER_TEST_SURELY_UNSET_VARIABLE', 'none') }}", "none");
+        test_template("{{ uuid() | length }}", "36");
+        test_template("{{ random_string(12) | length }}", "12");
+    }
+
+    #[test]
+    fn random_functions_are_reproducible_with_a_seed() {
+        let template = "{{ uuid() }} {{ random_string(8) }}";
+        let render = |seed: u64, name: &str| {
+            let mut renderer = MiniJinjaRenderer::new();
+            renderer.set_random_seed(seed);
+            renderer.render(template, &json!({}), Some(name)).unwrap()
+        };
+
+        assert_eq!(render(7, "README.md"), render(7, "README.md"));
+        assert_ne!(render(7, "README.md"), render(8, "README.md"));
+        assert_ne!(render(7, "README.md"), render(7, "LICENSE"));
+        let uuid = render(7, "README.md");
+        assert_eq!(&uuid[14..15], "4", "{uuid}");
+        // Seeds stored in `.baker-generated.yaml` must keep their values across builds
+        assert_eq!(
+            render(7, "README.md"),
+            "093d850f-d4ac-43fa-8a73-8dc37656a651 9KObTHzD"
+        );
+    }
+
+    #[test]
+    fn test_evaluate_expression_returns_value() {
+        let renderer = MiniJinjaRenderer::new();
+        let value = renderer
+            .evaluate_expression("name | trim | lower", &json!({ "name": "  My App " }))
+            .unwrap();
+        assert_eq!(value, json!("my app"));
+
+        let value =
+            renderer.evaluate_expression("items | length", &json!({ "items": [1, 2] }));
+        assert_eq!(value.unwrap(), json!(2));
+    }
+
     #[test]
     fn test_render_internal_non_object_context() {
         let renderer = MiniJinjaRenderer::new();
@@ -186,8 +438,9 @@ mod tests {
         let expected = "platform: ";
 
         let test_context = |context: serde_json::Value| {
-            let result =
-                renderer.render_internal(template, &context, None, None).unwrap();
+            let result = renderer
+                .render_internal(&renderer.env, template, &context, None)
+                .unwrap();
             assert_eq!(result, expected);
         };
 
@@ -236,4 +489,155 @@ mod tests {
             "Error should contain full relative path, got: {err_msg}"
         );
     }
+
+    #[test]
+    fn strict_undefined_rejects_unknown_variables() {
+        let mut renderer = MiniJinjaRenderer::new();
+        let context = json!({ "project_name": "baker" });
+        assert_eq!(renderer.render("{{ projct_name }}", &context, None).unwrap(), "");
+
+        renderer.set_strict_undefined(true);
+
+        let err = renderer
+            .render("line\n{{ projct_name }}", &context, Some("README.md"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("undefined value"), "{err}");
+        assert!(err.contains("README.md:2"), "{err}");
+        let err = renderer
+            .render_path(Path::new("src/{{ projct_name }}.rs"), &context)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("src/{{ projct_name }}.rs"), "{err}");
+        assert!(err.contains("undefined value"), "{err}");
+        assert_eq!(
+            renderer.render("{{ project_name }}", &context, None).unwrap(),
+            "baker"
+        );
+    }
+
+    #[test]
+    fn context_alias_exposes_answers_and_python_methods() {
+        let mut renderer = MiniJinjaRenderer::new();
+        renderer.set_context_alias("cookiecutter");
+        let context = json!({ "project_name": "My Project" });
+
+        let rendered = renderer
+            .render(
+                "{{ cookiecutter.project_name.lower().replace(' ', '_') }}",
+                &context,
+                None,
+            )
+            .unwrap();
+        assert_eq!(rendered, "my_project");
+        assert!(renderer
+            .execute_expression("cookiecutter.project_name == project_name", &context)
+            .unwrap());
+    }
+
+    #[test]
+    fn prelude_macros_and_globals_are_available_everywhere() {
+        let mut renderer = MiniJinjaRenderer::new();
+        renderer
+            .add_prelude(
+                "macros.j2",
+                "{% macro license(name) %}{{ name }} is {{ license_name }}{% endmacro %}\
+                 {% set year = 2024 %}",
+            )
+            .unwrap();
+        renderer.add_global("license_name", json!("MIT"));
+        renderer.add_template("base.j2", "[{% block body %}{% endblock %}]").unwrap();
+        let context = json!({ "project": "baker" });
+
+        let render = |template: &str| renderer.render(template, &context, None).unwrap();
+        assert_eq!(render("{{ license(project) }} ({{ year }})"), "baker is MIT (2024)");
+        assert_eq!(
+            render("{% extends 'base.j2' %}{% block body %}{{ license(project) }}{% endblock %}"),
+            "[baker is MIT]"
+        );
+        let path = renderer
+            .render_path(Path::new("{{ license_name | lower }}/{{ year }}.txt"), &context)
+            .unwrap();
+        assert_eq!(path, "mit/2024.txt");
+        assert!(renderer.execute_expression("license_name == 'MIT'", &context).unwrap());
+
+        let err = renderer
+            .render(
+                "line\n{{ license(project) }}\n{{ unclosed",
+                &context,
+                Some("README.md"),
+            )
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("README.md:3"), "{err}");
+    }
+
+    #[test]
+    fn custom_delimiters_apply_to_templates_paths_and_prelude() {
+        let mut renderer = MiniJinjaRenderer::new();
+        let delimiters = crate::renderer::Delimiters {
+            block_start: "[%".to_string(),
+            block_end: "%]".to_string(),
+            variable_start: "[[".to_string(),
+            variable_end: "]]".to_string(),
+            comment_start: "[#".to_string(),
+            comment_end: "#]".to_string(),
+        };
+        renderer.set_delimiters(&delimiters).unwrap();
+        renderer
+            .add_prelude("macros.j2", "[% macro shout(s) %][[ s | upper ]][% endmacro %]")
+            .unwrap();
+        let context = json!({ "name": "baker" });
+
+        let rendered = renderer
+            .render("[# note #][[ shout(name) ]] ${{ secrets.TOKEN }}", &context, None)
+            .unwrap();
+        assert_eq!(rendered, "BAKER ${{ secrets.TOKEN }}");
+        let path = renderer
+            .render_path(Path::new("[% if name %][[ name ]][% endif %].txt"), &context);
+        assert_eq!(path.unwrap(), "baker.txt");
+    }
+
+    fn template_globs(root: &Path, pattern: &str) -> globset::GlobSet {
+        let mut builder = globset::GlobSetBuilder::new();
+        builder.add(globset::Glob::new(&root.join(pattern).to_string_lossy()).unwrap());
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn template_dirs_are_loaded_on_first_use() {
+        let base = tempfile::tempdir().unwrap();
+        let child = tempfile::tempdir().unwrap();
+        let mut renderer = MiniJinjaRenderer::new();
+        renderer.add_template_dir(base.path(), template_globs(base.path(), "**/*.j2"));
+        renderer.add_template_dir(child.path(), template_globs(child.path(), "**/*.j2"));
+
+        // Files are only read when a template includes them.
+        std::fs::create_dir(base.path().join("partials")).unwrap();
+        std::fs::write(base.path().join("partials/name.j2"), "base {{ name }}").unwrap();
+        std::fs::write(base.path().join("shared.j2"), "base").unwrap();
+        std::fs::write(child.path().join("shared.j2"), "child").unwrap();
+        std::fs::write(base.path().join("skipped.txt"), "not a template").unwrap();
+
+        let context = json!({ "name": "baker" });
+        let render = |template: &str| renderer.render(template, &context, None);
+        assert_eq!(render("{% include 'partials/name.j2' %}").unwrap(), "base baker");
+        assert_eq!(render("{% include 'shared.j2' %}").unwrap(), "child");
+        assert!(render("{% include 'skipped.txt' %}").is_err());
+        assert!(render("{% include '../shared.j2' %}").is_err());
+    }
+
+    #[test]
+    fn include_errors_name_the_included_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("broken.j2"), "line one\n{{ unclosed").unwrap();
+        let mut renderer = MiniJinjaRenderer::new();
+        renderer.add_template_dir(dir.path(), template_globs(dir.path(), "*.j2"));
+
+        let err = renderer
+            .render("{% include 'broken.j2' %}", &json!({}), Some("README.md"))
+            .unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("broken.j2:2"), "got: {message}");
+    }
 }
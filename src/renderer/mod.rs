@@ -1,22 +1,191 @@
 //! Template rendering engine for Baker
 //!
-//! This module provides template rendering capabilities using MiniJinja.
+//! This module provides template rendering capabilities using MiniJinja, and
+//! optionally Handlebars or Tera.
 //! It includes various built-in filters for string manipulation and formatting.
 //!
 //! The module is structured as:
 //! - `interface`: Core trait definitions for template rendering
 //! - `minijinja`: MiniJinja-based implementation of the template renderer
+//! - `handlebars`: Handlebars-based implementation (`handlebars` feature)
+//! - `tera`: Tera-based implementation (`tera` feature)
 //! - `filters`: Custom filters for template processing
 
 pub mod filters;
+#[cfg(feature = "handlebars")]
+pub mod handlebars;
 pub mod interface;
 pub mod minijinja;
+#[cfg(feature = "tera")]
+pub mod tera;
+
+use crate::error::{Error, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 // Re-export the main types and traits for convenience
+#[cfg(feature = "handlebars")]
+pub use self::handlebars::HandlebarsRenderer;
+#[cfg(feature = "tera")]
+pub use self::tera::TeraRenderer;
 pub use interface::TemplateRenderer;
 pub use minijinja::MiniJinjaRenderer;
 
-/// Convenience function to create the default template renderer
-pub fn new_renderer() -> impl TemplateRenderer {
-    MiniJinjaRenderer::new()
+/// Template engine a template's files, paths and expressions are written for.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    /// MiniJinja, Baker's Jinja2-compatible engine
+    #[default]
+    Minijinja,
+    /// Handlebars, available when Baker is built with the `handlebars` feature
+    Handlebars,
+    /// Tera, available when Baker is built with the `tera` feature
+    Tera,
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Engine::Minijinja => write!(f, "minijinja"),
+            Engine::Handlebars => write!(f, "handlebars"),
+            Engine::Tera => write!(f, "tera"),
+        }
+    }
+}
+
+/// Delimiters of blocks, variables and comments in templates, file names and the
+/// templated values of `baker.yaml`. Only MiniJinja supports changing them.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Delimiters {
+    /// Start of a block such as `{% if %}` (default: `{%`).
+    pub block_start: String,
+    /// End of a block (default: `%}`).
+    pub block_end: String,
+    /// Start of a variable such as `{{ name }}` (default: `{{`).
+    pub variable_start: String,
+    /// End of a variable (default: `}}`).
+    pub variable_end: String,
+    /// Start of a comment such as `{# note #}` (default: `{#`).
+    pub comment_start: String,
+    /// End of a comment (default: `#}`).
+    pub comment_end: String,
+}
+
+impl Default for Delimiters {
+    fn default() -> Self {
+        Self {
+            block_start: "{%".to_string(),
+            block_end: "%}".to_string(),
+            variable_start: "{{".to_string(),
+            variable_end: "}}".to_string(),
+            comment_start: "{#".to_string(),
+            comment_end: "#}".to_string(),
+        }
+    }
+}
+
+impl Delimiters {
+    /// Returns `statement` as a block, e.g. `{% statement %}`.
+    pub fn block(&self, statement: &str) -> String {
+        format!("{} {statement} {}", self.block_start, self.block_end)
+    }
+}
+
+/// Creates a renderer for `engine`, failing if Baker was built without it.
+pub fn new_renderer(engine: Engine) -> Result<Box<dyn TemplateRenderer>> {
+    match engine {
+        Engine::Minijinja => Ok(Box::new(MiniJinjaRenderer::new())),
+        #[cfg(feature = "handlebars")]
+        Engine::Handlebars => Ok(Box::new(HandlebarsRenderer::new())),
+        #[cfg(feature = "tera")]
+        Engine::Tera => Ok(Box::new(TeraRenderer::new())),
+        #[allow(unreachable_patterns)]
+        engine => Err(Error::ConfigValidation(format!(
+            "the '{engine}' template engine is not available in this build of Baker; \
+             build it with `--features {engine}`"
+        ))),
+    }
+}
+
+/// Variables every render of the Handlebars and Tera renderers starts from.
+#[cfg(any(feature = "handlebars", feature = "tera"))]
+#[derive(Default)]
+struct SharedContext {
+    /// Values added with [`TemplateRenderer::add_global`]
+    globals: serde_json::Map<String, serde_json::Value>,
+    /// Variable that also holds the whole context, see [`TemplateRenderer::set_context_alias`]
+    alias: Option<String>,
+}
+
+#[cfg(any(feature = "handlebars", feature = "tera"))]
+impl SharedContext {
+    /// Returns `context` on top of the platform variables and the globals.
+    fn merge(&self, context: &serde_json::Value) -> serde_json::Value {
+        let mut merged = serde_json::Map::new();
+        merged.insert(
+            "platform".to_string(),
+            serde_json::json!({
+                "os": std::env::consts::OS,
+                "family": std::env::consts::FAMILY,
+                "arch": std::env::consts::ARCH,
+            }),
+        );
+        merged.extend(self.globals.clone());
+        if let Some(object) = context.as_object() {
+            merged.extend(object.clone());
+            if let Some(alias) = &self.alias {
+                merged.insert(alias.clone(), serde_json::Value::Object(object.clone()));
+            }
+        }
+        serde_json::Value::Object(merged)
+    }
+}
+
+/// Reads the files below `root` that match `globs`, named by their path relative to
+/// `root`, for engines that need every template registered up front.
+#[cfg(any(feature = "handlebars", feature = "tera"))]
+fn template_files(
+    root: &std::path::Path,
+    globs: &globset::GlobSet,
+) -> Vec<(String, String)> {
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && globs.is_match(entry.path()))
+        .filter_map(|entry| {
+            let name = entry.path().strip_prefix(root).ok()?.to_str()?.replace('\\', "/");
+            match std::fs::read_to_string(entry.path()) {
+                Ok(template) => Some((name, template)),
+                Err(e) => {
+                    log::warn!("Cannot read template '{name}': {e}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_renderer_fails_for_engines_left_out_of_the_build() {
+        assert!(new_renderer(Engine::Minijinja).is_ok());
+        assert_eq!(
+            new_renderer(Engine::Handlebars).is_ok(),
+            cfg!(feature = "handlebars")
+        );
+        let err = new_renderer(Engine::Tera).err().map(|e| e.to_string());
+        if cfg!(feature = "tera") {
+            assert!(err.is_none());
+        } else {
+            assert!(err.unwrap().contains("--features tera"));
+        }
+    }
 }
@@ -0,0 +1,262 @@
+//! Tera-based template renderer, for templates written for Tera.
+
+use super::{template_files, Delimiters, SharedContext};
+use crate::error::{Error, Result};
+use crate::ext::PathExt;
+use crate::renderer::interface::TemplateRenderer;
+use globset::GlobSet;
+use std::path::Path;
+use tera::{ast::Node, Context, Tera};
+
+/// Tera-based template rendering engine.
+///
+/// Tera always fails on undefined variables, whatever
+/// [`TemplateRenderer::set_strict_undefined`] says.
+pub struct TeraRenderer {
+    tera: Tera,
+    context: SharedContext,
+}
+
+impl TeraRenderer {
+    /// Creates a new TeraRenderer instance. Output is not HTML-escaped.
+    pub fn new() -> Self {
+        let mut tera = Tera::default();
+        tera.autoescape_on(Vec::new());
+        Self { tera, context: SharedContext::default() }
+    }
+}
+
+impl Default for TeraRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Converts a Tera error, whose causes hold the actual problem, into a Baker error.
+fn tera_error(e: tera::Error) -> Error {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(&e);
+    while let Some(cause) = source {
+        message = format!("{message}: {cause}");
+        source = cause.source();
+    }
+    Error::TeraError(message)
+}
+
+/// Whether any of `nodes`, at any depth, is an `{% include %}`.
+fn has_include(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::Include(..) => true,
+        Node::Block(_, block, _) => has_include(&block.body),
+        Node::MacroDefinition(_, definition, _) => has_include(&definition.body),
+        Node::FilterSection(_, section, _) => has_include(&section.body),
+        Node::Forloop(_, forloop, _) => {
+            has_include(&forloop.body)
+                || forloop.empty_body.as_deref().is_some_and(has_include)
+        }
+        Node::If(condition, _) => {
+            condition.conditions.iter().any(|(_, _, body)| has_include(body))
+                || condition.otherwise.as_ref().is_some_and(|(_, body)| has_include(body))
+        }
+        _ => false,
+    })
+}
+
+impl TemplateRenderer for TeraRenderer {
+    fn add_template(&mut self, name: &str, template: &str) -> Result<()> {
+        self.tera.add_raw_template(&name.replace('\\', "/"), template).map_err(tera_error)
+    }
+
+    fn add_prelude(&mut self, _name: &str, _template: &str) -> Result<()> {
+        Err(Error::UnsupportedByEngine {
+            engine: "tera".to_string(),
+            feature: "prelude templates".to_string(),
+        })
+    }
+
+    fn add_global(&mut self, name: &str, value: serde_json::Value) {
+        self.context.globals.insert(name.to_string(), value);
+    }
+
+    /// Registers every matching file right away, as Tera resolves `extends` when a
+    /// template is added.
+    fn add_template_dir(&mut self, import_root: &Path, globs: GlobSet) {
+        let templates = template_files(import_root, &globs);
+        if let Err(e) = self.tera.add_raw_templates(templates) {
+            log::warn!(
+                "Cannot load templates from {}: {}",
+                import_root.display(),
+                tera_error(e)
+            );
+        }
+    }
+
+    fn set_delimiters(&mut self, delimiters: &Delimiters) -> Result<()> {
+        if *delimiters == Delimiters::default() {
+            return Ok(());
+        }
+        Err(Error::UnsupportedByEngine {
+            engine: "tera".to_string(),
+            feature: "custom delimiters".to_string(),
+        })
+    }
+
+    fn set_context_alias(&mut self, name: &str) {
+        self.context.alias = Some(name.to_string());
+    }
+
+    /// Tera always fails on undefined variables.
+    fn set_strict_undefined(&mut self, _strict: bool) {}
+
+    /// Tera templates have no seeded random functions, so the seed is unused.
+    fn set_random_seed(&mut self, _seed: u64) {}
+
+    fn render(
+        &self,
+        template: &str,
+        context: &serde_json::Value,
+        template_name: Option<&str>,
+    ) -> Result<String> {
+        // Rendering a string registers it, so it happens in a registry of its own.
+        // Only templates that pull in others get the shared templates copied into
+        // it; doing so for every file, path and expression would be far too slow.
+        let name = template_name.unwrap_or("temp");
+        let parsed = tera::Template::new(name, None, template).map_err(tera_error)?;
+        let uses_registry = parsed.parent.is_some()
+            || !parsed.imported_macro_files.is_empty()
+            || has_include(&parsed.ast);
+        let mut tera = Tera::default();
+        tera.autoescape_on(Vec::new());
+        if uses_registry {
+            tera.extend(&self.tera).map_err(tera_error)?;
+        }
+        tera.add_raw_template(name, template).map_err(tera_error)?;
+        let context =
+            Context::from_value(self.context.merge(context)).map_err(tera_error)?;
+        tera.render(name, &context).map_err(tera_error)
+    }
+
+    fn render_path(
+        &self,
+        template_path: &Path,
+        context: &serde_json::Value,
+    ) -> Result<String> {
+        let path_str = template_path.to_str_checked()?;
+        self.render(path_str, context, None).map_err(|e| Error::ProcessError {
+            source_path: path_str.to_string(),
+            e: e.to_string(),
+        })
+    }
+
+    fn execute_expression(
+        &self,
+        expr_str: &str,
+        context: &serde_json::Value,
+    ) -> Result<bool> {
+        if expr_str.is_empty() {
+            return Ok(true);
+        }
+        let template = format!("{{% if {expr_str} %}}true{{% endif %}}");
+        Ok(self.render(&template, context, None)? == "true")
+    }
+
+    fn evaluate_expression(
+        &self,
+        expr_str: &str,
+        context: &serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let template = format!(
+            "{{% set baker_value = {expr_str} %}}{{{{ baker_value | json_encode() }}}}"
+        );
+        Ok(serde_json::from_str(&self.render(&template, context, None)?)?)
+    }
+
+    fn check_template(&self, template: &str, template_name: Option<&str>) -> Result<()> {
+        tera::Template::new(template_name.unwrap_or("temp"), None, template)
+            .map_err(tera_error)?;
+        Ok(())
+    }
+
+    fn check_expression(&self, expr_str: &str) -> Result<()> {
+        if !expr_str.is_empty() {
+            self.check_template(&format!("{{% if {expr_str} %}}{{% endif %}}"), None)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_templates_paths_and_expressions() {
+        let mut renderer = TeraRenderer::new();
+        renderer.add_template("base.tera", "[{% block body %}{% endblock %}]").unwrap();
+        renderer.add_global("license", json!("MIT"));
+        let context = json!({ "project_name": "<baker>", "items": [1, 2] });
+
+        let rendered = renderer
+            .render(
+                "{% extends \"base.tera\" %}{% block body %}{{ project_name }} {{ license }}{% endblock %}",
+                &context,
+                Some("README.md"),
+            )
+            .unwrap();
+        assert_eq!(rendered, "[<baker> MIT]");
+        let path = renderer
+            .render_path(Path::new("src/{{ project_name | lower }}.rs"), &context);
+        assert_eq!(path.unwrap(), "src/<baker>.rs");
+        assert!(renderer.execute_expression("items | length == 2", &context).unwrap());
+        assert_eq!(
+            renderer.evaluate_expression("items | length", &context).unwrap(),
+            json!(2)
+        );
+        let err =
+            renderer.render("{{ missing }}", &context, Some("README.md")).unwrap_err();
+        assert!(err.to_string().contains("missing"), "{err}");
+        assert!(renderer.check_template("{% if x %}", None).is_err());
+        assert!(renderer.add_prelude("macros.tera", "").is_err());
+    }
+
+    #[test]
+    fn includes_and_imports_resolve_against_registered_templates() {
+        let mut renderer = TeraRenderer::new();
+        renderer.add_template("header.tera", "# {{ title }}").unwrap();
+        renderer
+            .add_template(
+                "macros.tera",
+                "{% macro bold(text) %}**{{ text }}**{% endmacro %}",
+            )
+            .unwrap();
+        let context = json!({ "title": "Baker" });
+
+        let rendered = renderer
+            .render(
+                "{% import \"macros.tera\" as m %}{% include \"header.tera\" %} {{ m::bold(text=title) }}",
+                &context,
+                Some("README.md"),
+            )
+            .unwrap();
+        assert_eq!(rendered, "# Baker **Baker**");
+
+        let rendered = renderer
+            .render(
+                "{% for i in [1] %}{% if i %}{% include \"header.tera\" %}{% endif %}{% endfor %}",
+                &context,
+                None,
+            )
+            .unwrap();
+        assert_eq!(rendered, "# Baker");
+    }
+
+    #[test]
+    fn only_include_tags_count_as_includes() {
+        let parse = |template| tera::Template::new("t", None, template).unwrap().ast;
+        assert!(!has_include(&parse("#include <stdio.h>\n{{ title }} includes")));
+        assert!(has_include(&parse(
+            "{% block body %}{% include \"header.tera\" %}{% endblock %}"
+        )));
+    }
+}
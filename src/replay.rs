@@ -0,0 +1,119 @@
+//! Replay files — the last answers given for each template source, stored under the
+//! user data directory so that `baker generate --replay` can reuse them.
+
+use crate::{constants::REPLAY_DIR_ENV, error::Result, loader::TemplateSourceInfo};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// The structure serialised to `<replay dir>/<hash>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFile {
+    /// The template URL or path the answers belong to.
+    pub template: String,
+    /// RFC3339 timestamp of when the answers were saved.
+    pub saved_at: String,
+    /// The answers, without secrets.
+    pub answers: serde_json::Value,
+}
+
+/// Returns the directory replay files are stored in.
+///
+/// `BAKER_REPLAY_DIR` takes precedence; otherwise `$XDG_DATA_HOME/baker/replay`
+/// (`~/.local/share/baker/replay`, or `%LOCALAPPDATA%\baker\replay` on Windows).
+pub fn default_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(REPLAY_DIR_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+    let data_dir =
+        std::env::var_os("XDG_DATA_HOME").map(PathBuf::from).or_else(|| {
+            if cfg!(windows) {
+                std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+            } else {
+                std::env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".local/share"))
+            }
+        })?;
+    Some(data_dir.join("baker").join("replay"))
+}
+
+/// Returns the replay file of the template at `location`, see
+/// [`TemplateSourceInfo::canonical_location`].
+fn replay_path(dir: &Path, location: &str) -> PathBuf {
+    let digest = Sha256::digest(location.as_bytes());
+    dir.join(format!("{}.json", hex::encode(digest)))
+}
+
+/// Saves the answers for `source`, replacing any earlier replay file.
+///
+/// Callers are expected to strip secrets first, see
+/// [`crate::generated::strip_secret_answers`].
+pub fn save(
+    dir: &Path,
+    source: &TemplateSourceInfo,
+    answers: &serde_json::Value,
+) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let location = source.canonical_location();
+    let path = replay_path(dir, &location);
+    let data = ReplayFile {
+        template: location,
+        saved_at: Utc::now().to_rfc3339(),
+        answers: answers.clone(),
+    };
+    std::fs::write(&path, serde_json::to_string_pretty(&data)?)?;
+    log::debug!("Saved replay answers to '{}'", path.display());
+    Ok(())
+}
+
+/// Loads the answers last saved for `source`, if any.
+pub fn load(dir: &Path, source: &TemplateSourceInfo) -> Result<Option<ReplayFile>> {
+    let path = replay_path(dir, &source.canonical_location());
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn source(path: &str) -> TemplateSourceInfo {
+        TemplateSourceInfo::Filesystem { path: path.to_string(), hash: "abc".to_string() }
+    }
+
+    #[test]
+    fn save_and_load_round_trip_per_template() {
+        let dir = TempDir::new().unwrap();
+        let replay_dir = dir.path().join("replay");
+
+        save(&replay_dir, &source("/templates/a"), &json!({"name": "a"})).unwrap();
+        save(&replay_dir, &source("/templates/a"), &json!({"name": "a2"})).unwrap();
+
+        let loaded = load(&replay_dir, &source("/templates/a")).unwrap().unwrap();
+        assert_eq!(loaded.template, "/templates/a");
+        assert_eq!(loaded.answers, json!({"name": "a2"}));
+        assert!(load(&replay_dir, &source("/templates/b")).unwrap().is_none());
+    }
+
+    #[test]
+    fn relative_and_absolute_paths_share_the_replay_file() {
+        let dir = TempDir::new().unwrap();
+        let replay_dir = dir.path().join("replay");
+        let template = dir.path().join("tpl");
+        std::fs::create_dir(&template).unwrap();
+        let path = |path: std::path::PathBuf| source(path.to_str().unwrap());
+
+        save(&replay_dir, &path(template.join(".")), &json!({"name": "a"})).unwrap();
+
+        let loaded = load(&replay_dir, &path(template.join("../tpl"))).unwrap().unwrap();
+        assert_eq!(loaded.answers, json!({"name": "a"}));
+        let canonical = std::fs::canonicalize(&template).unwrap();
+        assert_eq!(loaded.template, canonical.to_string_lossy());
+    }
+}
@@ -4,12 +4,18 @@
 //! - `operation`: Defines operations to be performed on templates
 //! - `processor`: Contains the logic for processing template files and directories
 
-use crate::renderer::{new_renderer, TemplateRenderer};
+use crate::error::Result;
+use crate::renderer::{new_renderer, Engine, MiniJinjaRenderer, TemplateRenderer};
 
 pub mod operation;
 pub mod processor;
 
-/// Convenience function to create the default template engine
+/// Convenience function to create the default (MiniJinja) template engine
 pub fn get_template_engine() -> impl TemplateRenderer {
-    new_renderer()
+    MiniJinjaRenderer::new()
+}
+
+/// Creates the template engine selected by a template's `engine` setting.
+pub fn get_template_engine_for(engine: Engine) -> Result<Box<dyn TemplateRenderer>> {
+    new_renderer(engine)
 }
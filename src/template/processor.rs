@@ -1,5 +1,6 @@
 use crate::{
     cli::context::GenerationContext,
+    config::{cookiecutter::Cookiecutter, loader::build_globset},
     error::{Error, Result},
     ext::PathExt,
     renderer::TemplateRenderer,
@@ -11,6 +12,24 @@ use regex::Regex;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Error reported for entries whose path renders to nothing, see [`is_skipped_entry`].
+const INVALID_RENDERED_PATH: &str = "The rendered path is not valid";
+
+/// Whether `globs` match the entry at `relative_path` (below the template root) or
+/// one of its parent directories.
+pub fn matches_entry_or_parent(globs: &GlobSet, relative_path: &Path) -> bool {
+    relative_path
+        .ancestors()
+        .filter(|path| !path.as_os_str().is_empty())
+        .any(|path| globs.is_match(path))
+}
+
+/// Whether `error` reports an entry whose path rendered to nothing, such as a file in
+/// a `{% if %}` directory whose condition is false. Such entries are not generated.
+pub fn is_skipped_entry(error: &Error) -> bool {
+    matches!(error, Error::ProcessError { e, .. } if e == INVALID_RENDERED_PATH)
+}
+
 pub struct TemplateProcessor<'a, P: AsRef<Path>> {
     /// Dependencies
     engine: &'a dyn TemplateRenderer,
@@ -21,6 +40,12 @@ pub struct TemplateProcessor<'a, P: AsRef<Path>> {
     output_root: P,
     answers: &'a serde_json::Value,
     template_config: TemplateConfig<'a>,
+    /// Set for cookiecutter templates, whose files are all rendered.
+    cookiecutter: Option<&'a Cookiecutter>,
+    /// Files rendered even without the `template_suffix`.
+    render_globs: GlobSet,
+    /// Files and directories copied without rendering.
+    copy_without_render: GlobSet,
     loop_detector: Regex,
     loop_end_regex: Regex,
 }
@@ -29,6 +54,8 @@ pub struct TemplateConfig<'a> {
     pub template_suffix: &'a str,
     pub loop_separator: &'a str,
     pub loop_content_separator: &'a str,
+    /// Start of a variable, such as `{{`
+    pub variable_start: &'a str,
 }
 
 impl<'a> TemplateProcessor<'a, PathBuf> {
@@ -36,30 +63,55 @@ impl<'a> TemplateProcessor<'a, PathBuf> {
         engine: &'a dyn TemplateRenderer,
         context: &'a GenerationContext,
         bakerignore: &'a GlobSet,
-    ) -> Self {
+    ) -> Result<Self> {
         let config = context.config();
         let template_config = TemplateConfig {
-            template_suffix: config.template_suffix.as_str(),
-            loop_separator: config.loop_separator.as_str(),
-            loop_content_separator: config.loop_content_separator.as_str(),
+            template_suffix: config.settings.template_suffix.as_str(),
+            loop_separator: config.settings.loop_separator.as_str(),
+            loop_content_separator: config.settings.loop_content_separator.as_str(),
+            variable_start: config.settings.delimiters.variable_start.as_str(),
         };
+        let (block_start, block_end) = (
+            regex::escape(&config.settings.delimiters.block_start),
+            regex::escape(&config.settings.delimiters.block_end),
+        );
 
-        Self {
+        Ok(Self {
             engine,
             bakerignore,
-            template_root: context.template_root().clone(),
+            template_root: context.content_root(),
             output_root: context.output_root().clone(),
             answers: context.answers(),
             template_config,
-            loop_detector: Regex::new(r"\{\%\s*for\s+.*in.*\%\}")
-                .expect("valid for-loop regex"),
-            loop_end_regex: Regex::new(r"(\{\%\s*endfor\s*\%\})")
-                .expect("valid endfor regex"),
-        }
+            cookiecutter: config.cookiecutter.as_ref(),
+            render_globs: build_globset(&config.settings.render_globs)?,
+            copy_without_render: build_globset(&config.settings.copy_without_render)?,
+            loop_detector: Regex::new(&format!(
+                r"{block_start}\s*for\s+.*in.*{block_end}"
+            ))
+            .expect("valid for-loop regex"),
+            loop_end_regex: Regex::new(&format!(
+                r"({block_start}\s*endfor\s*{block_end})"
+            ))
+            .expect("valid endfor regex"),
+        })
+    }
+
+    /// Processes entries of `template_root` instead of the context's template root.
+    /// Used for parent templates pulled in through `extends`.
+    pub fn with_template_root(mut self, template_root: PathBuf) -> Self {
+        self.template_root = template_root;
+        self.cookiecutter = None;
+        self
     }
 }
 
 impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
+    /// The template directory whose entries this processor handles.
+    pub fn template_root(&self) -> &Path {
+        self.template_root.as_ref()
+    }
+
     /// Validates whether the `rendered_entry` is properly rendered by comparing its components
     /// with those of the original `template_entry`. The validation ensures no parts of the path
     /// are empty after rendering.
@@ -104,8 +156,9 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
                 return false;
             }
 
-            if template_part.starts_with("{{")
-                && !template_part.starts_with("{{.")
+            let variable_start = self.template_config.variable_start;
+            if template_part.starts_with(variable_start)
+                && !template_part[variable_start.len()..].starts_with('.')
                 && rendered_part.starts_with('.')
             {
                 return false;
@@ -133,6 +186,26 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         })
     }
 
+    /// Checks if the content of `template_entry` is rendered. Files matched by
+    /// `copy_without_render` never are. In a cookiecutter template every other file
+    /// not matched by `_copy_without_render` is rendered; otherwise files with the
+    /// `template_suffix` and files matched by `render_globs`.
+    fn is_rendered_file(&self, template_entry: &Path, rendered_entry: &Path) -> bool {
+        let relative = template_entry.strip_prefix(self.template_root.as_ref()).ok();
+        let matches = |globs: &GlobSet| {
+            relative.is_some_and(|relative| matches_entry_or_parent(globs, relative))
+        };
+        if matches(&self.copy_without_render) {
+            return false;
+        }
+        match self.cookiecutter {
+            Some(cookiecutter) => {
+                relative.is_some_and(|relative| !cookiecutter.is_copy_only(relative))
+            }
+            None => self.is_template_file(rendered_entry) || matches(&self.render_globs),
+        }
+    }
+
     /// Returns the relative path from template root for use in error messages.
     ///
     /// # Arguments
@@ -148,7 +221,8 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
             .map(|s| s.replace('\\', "/"))
     }
 
-    /// Renders a template entry path with template variables.
+    /// Renders a template entry path with template variables. Only the part below
+    /// the template root is rendered, so the root itself may contain `{{ }}`.
     ///
     /// # Arguments
     /// * `template_entry` - The template path to render
@@ -157,19 +231,30 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
     /// * `Result<PathBuf>` - The rendered path or an error
     ///
     fn render_template_entry(&self, template_entry: &Path) -> Result<PathBuf> {
-        let rendered_entry = self.engine.render_path(template_entry, self.answers)?;
+        let template_root = self.template_root.as_ref();
+        let Ok(relative_entry) = template_entry.strip_prefix(template_root) else {
+            return self.render_path(template_entry);
+        };
+        if relative_entry.as_os_str().is_empty() {
+            return Ok(template_root.to_path_buf());
+        }
+        Ok(template_root.join(self.render_path(relative_entry)?))
+    }
+
+    fn render_path(&self, template_path: &Path) -> Result<PathBuf> {
+        let rendered_path = self.engine.render_path(template_path, self.answers)?;
 
         if !self.rendered_path_has_valid_parts(
-            template_entry.to_str_checked()?,
-            &rendered_entry,
+            template_path.to_str_checked()?,
+            &rendered_path,
         ) {
             return Err(Error::ProcessError {
-                source_path: rendered_entry.to_string(),
-                e: "The rendered path is not valid".to_string(),
+                source_path: rendered_path.to_string(),
+                e: INVALID_RENDERED_PATH.to_string(),
             });
         }
 
-        Ok(PathBuf::from(rendered_entry))
+        Ok(PathBuf::from(rendered_path))
     }
 
     /// Removes the designated template suffix (by default it's `.baker.j2`) from a template file path.
@@ -230,9 +315,11 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         let template_entry = template_entry.as_ref().to_path_buf();
         let rendered_entry = self.render_template_entry(&template_entry)?;
         let target_path = self.get_target_path(&rendered_entry, &template_entry)?;
+        let is_rendered = self.is_rendered_file(&template_entry, &rendered_entry);
         // For template files the output path has the suffix stripped, so we must
         // compute target_exists after stripping to correctly detect pre-existing files.
-        let final_target_path = if self.is_template_file(&rendered_entry)
+        let final_target_path = if is_rendered
+            && self.is_template_file(&rendered_entry)
             && !self.is_template_with_loop(&template_entry)
         {
             self.remove_template_suffix(&target_path)?
@@ -247,13 +334,32 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         }
 
         // Handle different types of entries
-        match (template_entry.is_file(), self.is_template_file(&rendered_entry)) {
+        match (template_entry.is_file(), is_rendered) {
             // Template file
             (true, true) => {
-                let template_content = fs::read_to_string(&template_entry)?;
-                let template_name =
-                    template_entry.file_name().and_then(|name| name.to_str());
+                let template_content = match fs::read_to_string(&template_entry) {
+                    Ok(content) => content,
+                    // Like cookiecutter, copy binary files instead of rendering them,
+                    // also when they are only rendered because of `render_globs`
+                    Err(e)
+                        if (self.cookiecutter.is_some()
+                            || !self.is_template_file(&rendered_entry))
+                            && e.kind() == std::io::ErrorKind::InvalidData =>
+                    {
+                        return Ok(TemplateOperation::Copy {
+                            source: template_entry,
+                            target: target_path,
+                            target_exists,
+                        });
+                    }
+                    Err(e) => return Err(e.into()),
+                };
                 let relative_path = self.get_template_name(&template_entry);
+                // Named by its relative path, which error messages show and which
+                // makes seeded random values differ between files
+                let template_name = relative_path.as_deref().or_else(|| {
+                    template_entry.file_name().and_then(|name| name.to_str())
+                });
                 if self.is_template_with_loop(&template_entry) {
                     debug!("Processing loop template file: {}", template_entry.display());
                     return self
@@ -264,6 +370,7 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
                     .render(&template_content, self.answers, template_name)
                     .map_err(|e| Error::ProcessError {
                         source_path: relative_path
+                            .clone()
                             .unwrap_or_else(|| template_entry.display().to_string()),
                         e: e.to_string(),
                     })?;
@@ -292,7 +399,7 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
     ///
     /// # Arguments
     /// * `template_entry` - The template path to render
-    /// * `template_name` - The name of the template file
+    /// * `template_name` - The name of the template, its path relative to the template root
     ///
     /// # Returns
     /// * `Result<String>` - The rendered content with injected values
@@ -319,8 +426,11 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         );
         let raw_template_content = fs::read_to_string(template_entry)?;
         debug!("Raw loop template content: {raw_template_content}");
+        let relative_entry = template_entry
+            .strip_prefix(self.template_root.as_ref())
+            .unwrap_or(template_entry);
         let template_with_injected_content =
-            self.inject_loop_content(template_entry, &raw_template_content)?;
+            self.inject_loop_content(relative_entry, &raw_template_content)?;
         debug!("Loop template after content injection: {template_with_injected_content}");
         let rendered_content = self
             .engine
@@ -377,13 +487,20 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
         rendered_parent_dir: &Path,
         template_entry: &Path,
     ) -> Result<Vec<WriteOp>> {
+        // The loop was rendered from the path below the template root, so only the
+        // first file name carries the parent directories.
+        let template_root = self.template_root.as_ref();
+        let relative_parent_dir = rendered_parent_dir
+            .strip_prefix(template_root)
+            .unwrap_or(rendered_parent_dir);
         self.split_content(rendered_content)
             .into_iter()
             .map(|(rendered_filename, content)| {
                 let mut output_file_path = PathBuf::from(&rendered_filename);
-                if !output_file_path.starts_with(rendered_parent_dir) {
-                    output_file_path = rendered_parent_dir.join(&rendered_filename);
+                if !output_file_path.starts_with(relative_parent_dir) {
+                    output_file_path = relative_parent_dir.join(&rendered_filename);
                 }
+                let output_file_path = template_root.join(output_file_path);
                 let final_output_path =
                     self.get_target_path(&output_file_path, template_entry)?;
                 let target_exists = final_output_path.exists();
@@ -401,7 +518,9 @@ impl<'a, P: AsRef<Path>> TemplateProcessor<'a, P> {
 mod tests {
     use super::*;
     use crate::{
-        cli::context::GenerationContext, config::ConfigV1, renderer::MiniJinjaRenderer,
+        cli::context::GenerationContext,
+        config::{ConfigV2, TemplateSettings},
+        renderer::MiniJinjaRenderer,
         template::operation::TemplateOperation,
     };
     use fs::File;
@@ -414,6 +533,22 @@ mod tests {
     fn new_test_processor(
         answers: serde_json::Value,
     ) -> (TempDir, TempDir, TemplateProcessor<'static, PathBuf>) {
+        new_test_processor_with_globs(answers, &[], &[])
+    }
+
+    fn new_test_processor_with_globs(
+        answers: serde_json::Value,
+        render_globs: &[&str],
+        copy_without_render: &[&str],
+    ) -> (TempDir, TempDir, TemplateProcessor<'static, PathBuf>) {
+        try_new_test_processor(answers, render_globs, copy_without_render).unwrap()
+    }
+
+    fn try_new_test_processor(
+        answers: serde_json::Value,
+        render_globs: &[&str],
+        copy_without_render: &[&str],
+    ) -> Result<(TempDir, TempDir, TemplateProcessor<'static, PathBuf>)> {
         let template_root = TempDir::new().unwrap();
         let output_root = TempDir::new().unwrap();
         let engine = Box::new(MiniJinjaRenderer::new());
@@ -422,21 +557,33 @@ mod tests {
         let mut context = GenerationContext::new(
             template_root.path().to_path_buf(),
             output_root.path().to_path_buf(),
-            ConfigV1 {
-                template_suffix: ".baker.j2".into(),
-                loop_separator: "".into(),
-                loop_content_separator: "".into(),
-                template_globs: Vec::new(),
-                import_root: None,
+            ConfigV2 {
+                settings: TemplateSettings {
+                    engine: Default::default(),
+                    delimiters: Default::default(),
+                    template_suffix: ".baker.j2".into(),
+                    loop_separator: "".into(),
+                    loop_content_separator: "".into(),
+                    template_globs: Vec::new(),
+                    import_root: None,
+                    render_globs: render_globs.iter().map(|s| s.to_string()).collect(),
+                    copy_without_render: copy_without_render
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect(),
+                    follow_symlinks: false,
+                    generated_file_name: None,
+                    conflict_marker_style: None,
+                    extends: None,
+                    validations: Vec::new(),
+                    strict_undefined: false,
+                    prelude: Vec::new(),
+                    globals: Default::default(),
+                    allow_unknown_keys: false,
+                },
                 questions: IndexMap::new(),
-                post_hook_filename: "post".into(),
-                pre_hook_filename: "pre".into(),
-                post_hook_runner: Vec::new(),
-                pre_hook_runner: Vec::new(),
-                post_hook_print_stdout: false,
-                follow_symlinks: false,
-                generated_file_name: None,
-                conflict_marker_style: None,
+                hooks: Default::default(),
+                cookiecutter: None,
             },
             Vec::new(),
             false,
@@ -450,8 +597,8 @@ mod tests {
             Box::leak(engine),
             context,
             &*Box::leak(Box::new(bakerignore)),
-        );
-        (template_root, output_root, processor)
+        )?;
+        Ok((template_root, output_root, processor))
     }
 
     /// The template structure
@@ -724,6 +871,80 @@ mod tests {
         }
     }
 
+    /// The template structure
+    /// template_root/
+    ///   config/app.toml
+    ///
+    /// Expected output
+    /// output_root/
+    ///   config/app.toml
+    ///
+    /// Because `render_globs` is `["**/*.toml"]` and answers are
+    /// {"name": "demo"}
+    ///
+    #[test]
+    fn renders_file_matched_by_render_globs_in_place() {
+        let answers = json!({"name": "demo"});
+        let (template_root, output_root, processor) =
+            new_test_processor_with_globs(answers, &["**/*.toml"], &[]);
+        std::fs::create_dir(template_root.path().join("config")).unwrap();
+        let file_path = template_root.path().join("config/app.toml");
+        std::fs::write(&file_path, "name = \"{{ name }}\"").unwrap();
+        match processor.process(file_path).unwrap() {
+            TemplateOperation::Write { target, content, .. } => {
+                assert_eq!(target, output_root.path().join("config/app.toml"));
+                assert_eq!(content, "name = \"demo\"");
+            }
+            _ => panic!("Expected Write operation"),
+        }
+    }
+
+    /// The template structure
+    /// template_root/
+    ///   static/page.html.baker.j2
+    ///
+    /// Expected output
+    /// output_root/
+    ///   static/page.html.baker.j2
+    ///
+    /// Because `copy_without_render` is `["static"]`, which also beats `render_globs`
+    ///
+    #[test]
+    fn copies_file_matched_by_copy_without_render_verbatim() {
+        let (template_root, output_root, processor) =
+            new_test_processor_with_globs(json!({}), &["**/*.html*"], &["static"]);
+        std::fs::create_dir(template_root.path().join("static")).unwrap();
+        let file_path = template_root.path().join("static/page.html.baker.j2");
+        std::fs::write(&file_path, "{{ title }}").unwrap();
+        match processor.process(file_path.clone()).unwrap() {
+            TemplateOperation::Copy { source, target, .. } => {
+                assert_eq!(source, file_path);
+                assert_eq!(target, output_root.path().join("static/page.html.baker.j2"));
+            }
+            _ => panic!("Expected Copy operation"),
+        }
+    }
+
+    #[test]
+    fn invalid_globs_are_an_error_without_validating_the_config() {
+        let result = try_new_test_processor(json!({}), &["src/["], &[]);
+        assert!(matches!(result, Err(Error::GlobSetParseError(_))));
+    }
+
+    #[test]
+    fn copies_binary_file_matched_by_render_globs() {
+        let (template_root, output_root, processor) =
+            new_test_processor_with_globs(json!({}), &["*"], &[]);
+        let file_path = template_root.path().join("logo.png");
+        std::fs::write(&file_path, [0x89, b'P', b'N', b'G', 0xff, 0xfe]).unwrap();
+        match processor.process(file_path).unwrap() {
+            TemplateOperation::Copy { target, .. } => {
+                assert_eq!(target, output_root.path().join("logo.png"));
+            }
+            _ => panic!("Expected Copy operation"),
+        }
+    }
+
     /// The template structure
     /// template_root/
     ///   README.baker.j2
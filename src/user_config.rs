@@ -0,0 +1,182 @@
+//! Per-user defaults file (`~/.config/baker/defaults.yaml`).
+//!
+//! The file provides the lowest-priority answers for every template, plus overrides
+//! for specific templates keyed by their URL or path:
+//!
+//! ```yaml
+//! default_answers:
+//!   author_name: Jane Doe
+//!   author_email: jane@example.com
+//! templates:
+//!   https://github.com/acme/service-template:
+//!     default_answers:
+//!       github_org: acme
+//! ```
+
+use crate::{
+    constants::USER_CONFIG_ENV,
+    error::{Error, Result},
+    loader::{canonical_template_location, TemplateSourceInfo},
+};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Contents of the per-user defaults file.
+#[derive(Debug, Default, Deserialize)]
+pub struct UserConfig {
+    /// Answers applied to every template
+    #[serde(default)]
+    pub default_answers: Map<String, Value>,
+    /// Overrides for specific templates, keyed by template URL or path
+    #[serde(default)]
+    pub templates: IndexMap<String, TemplateDefaults>,
+}
+
+/// Per-template section of the user defaults file.
+#[derive(Debug, Default, Deserialize)]
+pub struct TemplateDefaults {
+    /// Answers applied to this template only
+    #[serde(default)]
+    pub default_answers: Map<String, Value>,
+}
+
+impl UserConfig {
+    /// Returns the location of the user defaults file.
+    ///
+    /// `BAKER_USER_CONFIG` takes precedence; otherwise the file lives in
+    /// `$XDG_CONFIG_HOME/baker` (`~/.config/baker`, or `%APPDATA%\baker` on Windows).
+    pub fn default_path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(USER_CONFIG_ENV) {
+            return Some(PathBuf::from(path));
+        }
+        let config_dir =
+            std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|| {
+                if cfg!(windows) {
+                    std::env::var_os("APPDATA").map(PathBuf::from)
+                } else {
+                    std::env::var_os("HOME")
+                        .map(|home| PathBuf::from(home).join(".config"))
+                }
+            })?;
+        Some(config_dir.join("baker").join("defaults.yaml"))
+    }
+
+    /// Loads the user defaults file, returning an empty configuration when it does
+    /// not exist.
+    pub fn load() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Loads a user defaults file from `path`.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        log::debug!("Loading user defaults from {}", path.display());
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content).map_err(|e| {
+            Error::ConfigValidation(format!(
+                "invalid user defaults file '{}': {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Returns the default answers for a template: the global answers overlaid with
+    /// every `templates` entry that matches the template argument or its source.
+    ///
+    /// Keys are compared in their canonical form, see
+    /// [`TemplateSourceInfo::canonical_location`]; relative paths are resolved
+    /// against the current directory.
+    pub fn answers_for(
+        &self,
+        template: &str,
+        source: &TemplateSourceInfo,
+    ) -> Map<String, Value> {
+        let mut answers = self.default_answers.clone();
+        let locations =
+            [canonical_template_location(template), source.canonical_location()];
+        for (key, defaults) in &self.templates {
+            if locations.contains(&canonical_template_location(key)) {
+                answers.extend(defaults.default_answers.clone());
+            }
+        }
+        answers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn template_overrides_apply_on_top_of_global_answers() {
+        let config: UserConfig = serde_yaml::from_str(
+            r#"
+default_answers:
+  author_name: Jane
+  github_org: jane
+templates:
+  https://github.com/acme/service-template:
+    default_answers:
+      github_org: acme
+  ./other:
+    default_answers:
+      github_org: other
+"#,
+        )
+        .unwrap();
+        let source = TemplateSourceInfo::Git {
+            url: "https://github.com/acme/service-template.git".to_string(),
+            commit: "abc".to_string(),
+            tag: None,
+        };
+
+        let answers = config.answers_for("gh:acme/service-template", &source);
+
+        assert_eq!(json!(answers), json!({"author_name": "Jane", "github_org": "acme"}));
+    }
+
+    #[test]
+    fn template_overrides_match_paths_in_any_form() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("tpl");
+        std::fs::create_dir(&template).unwrap();
+        let mut config = UserConfig::default();
+        let key = template.join("../tpl/").to_string_lossy().to_string();
+        config.templates.insert(
+            key,
+            TemplateDefaults {
+                default_answers: Map::from_iter([("org".to_string(), json!("acme"))]),
+            },
+        );
+        let source = TemplateSourceInfo::Filesystem {
+            path: template.join(".").to_string_lossy().to_string(),
+            hash: "abc".to_string(),
+        };
+
+        let answers = config.answers_for("unrelated", &source);
+
+        assert_eq!(json!(answers), json!({"org": "acme"}));
+    }
+
+    #[test]
+    fn load_from_reads_yaml_and_reports_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("defaults.yaml");
+
+        std::fs::write(&path, "default_answers:\n  author_name: Jane\n").unwrap();
+        let config = UserConfig::load_from(&path).unwrap();
+        assert_eq!(config.default_answers["author_name"], json!("Jane"));
+        assert!(config.templates.is_empty());
+
+        std::fs::write(&path, "default_answers: [1, 2]\n").unwrap();
+        assert!(matches!(
+            UserConfig::load_from(&path),
+            Err(Error::ConfigValidation(ref msg)) if msg.contains("invalid user defaults")
+        ));
+    }
+}
@@ -0,0 +1,38 @@
+use std::fs;
+use std::process::Command;
+
+/// `baker generate <template> .` stages next to the current directory, so neither
+/// the staging nor the backup directory ends up in the generated project.
+#[test]
+fn generates_into_current_directory() {
+    let home = tempfile::tempdir().unwrap();
+    let parent = tempfile::tempdir().unwrap();
+    let output_root = parent.path().join("project");
+    fs::create_dir(&output_root).unwrap();
+    fs::write(output_root.join("existing.txt"), "kept").unwrap();
+    let template = fs::canonicalize("tests/templates/render_globs").unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_baker"))
+        .current_dir(&output_root)
+        .env("HOME", home.path())
+        .env("XDG_CONFIG_HOME", home.path())
+        .env("XDG_DATA_HOME", home.path())
+        .args(["generate", template.to_str().unwrap(), "."])
+        .args(["--answers", r#"{"project_name": "demo"}"#])
+        .args(["--force", "--non-interactive", "--skip-confirms", "all"])
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+    assert_eq!(fs::read_to_string(output_root.join("existing.txt")).unwrap(), "kept");
+    assert!(output_root.join("README.md").exists());
+    let leftovers: Vec<_> = fs::read_dir(&output_root)
+        .unwrap()
+        .chain(fs::read_dir(parent.path()).unwrap())
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            name.contains(".baker-staging-") || name.contains(".baker-backup-")
+        })
+        .collect();
+    assert!(leftovers.is_empty(), "left behind: {leftovers:?}");
+}
@@ -0,0 +1 @@
+"""My Project package."""
\ No newline at end of file
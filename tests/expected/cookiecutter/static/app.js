@@ -0,0 +1 @@
+const greeting = "{{ not rendered }}";
@@ -663,8 +663,17 @@ fn test_jsonschema_file_template_from_gitea() {
         force: true,
         answers: None,
         answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -857,8 +866,17 @@ fn test_template_with_submodule_schema_file() {
         force: true,
         answers: None,
         answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -1046,8 +1064,17 @@ Entities count: {{ entities | length }}
         force: true,
         answers: None,
         answers_file: Some(answers_file),
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -156,8 +156,17 @@ fn test_non_interactive_mode_with_defaults() {
         force: true,
         answers: None, // Test default values being used
         answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -188,8 +197,17 @@ fn test_nested_answer_context() {
         force: true,
         answers: Some(r#"{"project_name": "Test Project", "project_author": "Test Author", "project_slug": "test_project", "use_tests": true}"#.to_string()),
         answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -290,8 +308,17 @@ fn test_answers_file() {
         force: true,
         answers: None,
         answers_file: Some(answers_file),
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -329,8 +356,17 @@ fn test_answers_file_with_cli_override() {
         force: true,
         answers: Some(r#"{"project_name": "CLI Override"}"#.to_string()),
         answers_file: Some(answers_file),
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -343,3 +379,129 @@ fn test_answers_file_with_cli_override() {
     assert!(content.contains("CLI Override"));
     assert!(content.contains("File Author"));
 }
+
+#[test]
+fn test_extends_layers_parent_template() {
+    run_and_assert(
+        "tests/templates/extends_child",
+        "tests/expected/extends",
+        Some(r#"{"project_name": "Child"}"#),
+    );
+}
+
+#[test]
+fn test_replay_reuses_answers_from_last_run() {
+    let data_dir = tempfile::tempdir().unwrap();
+    std::env::set_var("BAKER_REPLAY_DIR", data_dir.path());
+    std::env::set_var("BAKER_USER_CONFIG", data_dir.path().join("defaults.yaml"));
+
+    let generate = |output_dir: &std::path::Path, answers: Option<&str>, replay: bool| {
+        run(GenerateArgs {
+            template: "examples/demo".to_string(),
+            output_dir: output_dir.to_path_buf(),
+            force: true,
+            answers: answers.map(str::to_string),
+            answers_file: None,
+            answers_format: Default::default(),
+            jobs: None,
+            on_error: Default::default(),
+            strict_undefined: false,
+            random_seed: None,
+            skip_confirms: vec![All],
+            non_interactive: true,
+            strict: false,
+            no_user_config: false,
+            replay,
+            no_replay: false,
+            dry_run: false,
+            generated_file: None,
+            conflict_style: None,
+        })
+    };
+
+    let first = tempfile::tempdir().unwrap();
+    let answers = r#"{"project_name": "Replayed", "project_author": "Replay Author", "project_slug": "replayed", "use_tests": true}"#;
+    generate(first.path(), Some(answers), false).unwrap();
+
+    let second = tempfile::tempdir().unwrap();
+    generate(second.path(), None, true).unwrap();
+
+    let content = std::fs::read_to_string(second.path().join("CONTRIBUTING.md")).unwrap();
+    assert!(content.contains("Replayed"));
+    assert!(content.contains("Replay Author"));
+
+    let unknown = tempfile::tempdir().unwrap();
+    let err = run(GenerateArgs {
+        template: "tests/templates/builtin_filters".to_string(),
+        output_dir: unknown.path().to_path_buf(),
+        force: true,
+        answers: None,
+        answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
+        skip_confirms: vec![All],
+        non_interactive: true,
+        strict: false,
+        no_user_config: false,
+        replay: true,
+        no_replay: false,
+        dry_run: false,
+        generated_file: None,
+        conflict_style: None,
+    });
+    assert!(matches!(err, Err(baker::error::Error::ReplayNotFound { .. })));
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn test_on_error_warn_skips_post_hook_and_records_failures() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let template = tmp_dir.path().join("template");
+    let marker = tmp_dir.path().join("post_hook_ran");
+    std::fs::create_dir_all(template.join("hooks")).unwrap();
+    std::fs::write(
+        template.join("baker.yaml"),
+        "schemaVersion: v2\nhooks:\n  post:\n    runner: [\"sh\"]\nquestions: {}\n",
+    )
+    .unwrap();
+    std::fs::write(
+        template.join("hooks/post"),
+        format!("cat > /dev/null\ntouch '{}'\n", marker.display()),
+    )
+    .unwrap();
+    std::fs::write(template.join("good.txt.baker.j2"), "fine").unwrap();
+    std::fs::write(template.join("broken.txt.baker.j2"), "{% if %}").unwrap();
+
+    let output_dir = tmp_dir.path().join("output");
+    let result = run(GenerateArgs {
+        template: template.to_string_lossy().to_string(),
+        output_dir: output_dir.clone(),
+        force: true,
+        answers: Some("{}".to_string()),
+        answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: baker::cli::OnError::Warn,
+        strict_undefined: false,
+        random_seed: None,
+        skip_confirms: vec![All],
+        non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
+        dry_run: false,
+        generated_file: None,
+        conflict_style: None,
+    });
+
+    assert!(matches!(result, Err(baker::error::Error::GenerationFailed { .. })));
+    assert!(output_dir.join("good.txt").exists());
+    assert!(!marker.exists(), "the post hook ran for a failed generation");
+    let meta = baker::generated::read(&output_dir, ".baker-generated.yaml").unwrap();
+    assert_eq!(meta.failures.len(), 1);
+    assert!(meta.failures[0].contains("broken.txt.baker.j2"), "{:?}", meta.failures);
+}
@@ -33,6 +33,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cookiecutter_template() {
+        run_and_assert(
+            "tests/templates/cookiecutter",
+            "tests/expected/cookiecutter",
+            None,
+        );
+    }
+
     #[test]
     fn test_jsonschema_default() {
         run_and_assert(
@@ -124,6 +133,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_custom_delimiters() {
+        run_and_assert(
+            "tests/templates/custom_delimiters",
+            "tests/expected/custom_delimiters",
+            Some("{\"app_name\": \"shop\"}"),
+        );
+    }
+
+    #[test]
+    fn test_render_globs() {
+        run_and_assert(
+            "tests/templates/render_globs",
+            "tests/expected/render_globs",
+            Some("{\"project_name\": \"demo\"}"),
+        );
+    }
+
     #[test]
     fn test_symlinks_copy() {
         run_and_assert("tests/templates/symlinks", "tests/expected/symlinks", None);
@@ -21,6 +21,15 @@ use walkdir::WalkDir;
 
 /// Run `baker generate` into a fresh temp dir and return the temp dir.
 fn generate_into_tmp(template: &str, answers: Option<&str>) -> TempDir {
+    generate_into_tmp_with_seed(template, answers, None)
+}
+
+/// Like [`generate_into_tmp`], passing `random_seed` as `--random-seed`.
+fn generate_into_tmp_with_seed(
+    template: &str,
+    answers: Option<&str>,
+    random_seed: Option<u64>,
+) -> TempDir {
     let tmp = TempDir::new().unwrap();
     let args = GenerateArgs {
         template: template.to_string(),
@@ -28,8 +37,17 @@ fn generate_into_tmp(template: &str, answers: Option<&str>) -> TempDir {
         force: true,
         answers: answers.map(|s| s.to_string()),
         answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -52,10 +70,17 @@ fn run_update_in(output_dir: &Path, extra_answers: Option<&str>) {
         generated_file: None,
         answers: extra_answers.map(|s| s.to_string()),
         answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         conflict_style: None,
         dry_run: false,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
     };
     run_update_in_dir(args, output_dir.to_path_buf()).unwrap();
 }
@@ -75,10 +100,17 @@ fn run_update_in_expect_err_with(
         generated_file: None,
         answers: answers.map(|s| s.to_string()),
         answers_file: answers_file.map(std::path::PathBuf::from),
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         conflict_style: None,
         dry_run: false,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
     };
     format!("{}", run_update_in_dir(args, output_dir.to_path_buf()).unwrap_err())
 }
@@ -195,6 +227,29 @@ fn update_local_template_metadata_is_refreshed() {
     }
 }
 
+/// The seed reveals every random value, so it is only recorded when it was chosen
+/// explicitly, and `update` keeps rendering with it from then on.
+#[test]
+fn random_seed_is_recorded_only_when_given() {
+    let template_dir = TempDir::new().unwrap();
+    create_simple_template(template_dir.path(), "{{ uuid() }}");
+    let template = template_dir.path().to_str().unwrap();
+
+    let unseeded = generate_into_tmp(template, None);
+    assert_eq!(read_meta(unseeded.path()).random_seed, None);
+
+    let seeded = generate_into_tmp_with_seed(template, None, Some(7));
+    assert_eq!(read_meta(seeded.path()).random_seed, Some(7));
+    let uuid = fs::read_to_string(seeded.path().join("README.md")).unwrap();
+
+    write_template_file(template_dir.path(), "{{ uuid() }}!");
+    run_update_in(seeded.path(), None);
+    assert_eq!(read_meta(seeded.path()).random_seed, Some(7));
+    // Both sides of the conflict carry the same value.
+    let updated = fs::read_to_string(seeded.path().join("README.md")).unwrap();
+    assert!(updated.contains(&format!("{uuid}\n=======\n{uuid}!")), "{updated}");
+}
+
 #[test]
 fn update_fails_when_no_generated_file() {
     let empty_dir = TempDir::new().unwrap();
@@ -255,10 +310,17 @@ fn update_local_template_dry_run_no_changes() {
             generated_file: None,
             answers: None,
             answers_file: None,
+            answers_format: Default::default(),
+            jobs: None,
+            on_error: Default::default(),
+            strict_undefined: false,
+            random_seed: None,
             conflict_style: None,
             dry_run: true,
             skip_confirms: vec![All],
             non_interactive: true,
+            strict: false,
+            no_user_config: true,
         },
         output_dir.path().to_path_buf(),
     )
@@ -492,8 +554,17 @@ questions:
         force: true,
         answers: Some(r#"{"name": "Alice", "password": "hunter2"}"#.to_string()),
         answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,
@@ -509,6 +580,30 @@ questions:
     );
 }
 
+/// Changing only the parent template of an `extends` chain should still trigger an
+/// update, and the chain should be recorded in the metadata file.
+#[test]
+fn update_reruns_when_parent_template_changes() {
+    let templates = TempDir::new().unwrap();
+    let base = templates.path().join("base");
+    let child = templates.path().join("child");
+    fs::create_dir_all(&base).unwrap();
+    fs::create_dir_all(&child).unwrap();
+    create_simple_template(&base, "Hello, {{name}}!");
+    fs::write(base.join("LICENSE.baker.j2"), "MIT for {{name}}").unwrap();
+    fs::write(child.join("baker.yaml"), "schemaVersion: v1\nextends: ../base\n").unwrap();
+
+    let output_dir =
+        generate_into_tmp(child.to_str().unwrap(), Some(r#"{"name": "Alice"}"#));
+    assert_eq!(read_meta(output_dir.path()).extends.len(), 1);
+
+    fs::write(base.join("LICENSE.baker.j2"), "Apache-2.0 for {{name}}").unwrap();
+    run_update_in(output_dir.path(), None);
+
+    let license = fs::read_to_string(output_dir.path().join("LICENSE")).unwrap();
+    assert!(license.contains("Apache-2.0 for Alice"), "got: {license}");
+}
+
 const DEMO_ANSWERS: &str = r#"{"project_name": "demo", "project_author": "demo", "project_slug": "demo", "use_tests": true}"#;
 
 /// Copy a directory tree from `src` (relative to the workspace root) into a
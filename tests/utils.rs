@@ -103,8 +103,17 @@ pub fn run_and_assert(template: &str, expected_dir: &str, answers: Option<&str>)
         force: true,
         answers: answers.map(|a| a.to_string()),
         answers_file: None,
+        answers_format: Default::default(),
+        jobs: None,
+        on_error: Default::default(),
+        strict_undefined: false,
+        random_seed: None,
         skip_confirms: vec![All],
         non_interactive: true,
+        strict: false,
+        no_user_config: true,
+        replay: false,
+        no_replay: true,
         dry_run: false,
         generated_file: None,
         conflict_style: None,